@@ -1,6 +1,4 @@
-use std::ops::{Index, IndexMut};
-
-use crate::{type_alias_default::TypeAliasDefault, Board, Color};
+use crate::{type_alias_default::TypeAliasDefault, Board, ByColor};
 
 pub const SIZE: u64 = Board::SIZE as u64;
 pub const HEIGHT: u64 = Board::HEIGHT as u64;
@@ -31,6 +29,47 @@ pub const WEST: usize = 1;
 /// `board >> NO_WE` or `idx - NO_WE`
 pub const NO_WE: usize = 9;
 
+/// All squares except the ones on the A file.
+pub const NOT_FILE_A: u64 = 18374403900871474942;
+/// All squares except the ones on the H file.
+pub const NOT_FILE_H: u64 = 9187201950435737471;
+
+/// Shifts every bit one square towards the top of the board (rank 8).
+///
+/// Bits on the last rank are discarded. The same goes for all other shift
+/// functions, bits are never wrapped around to the other side of the board.
+pub fn shift_north(board: u64) -> u64 {
+    board >> NORTH
+}
+
+pub fn shift_no_ea(board: u64) -> u64 {
+    (board & NOT_FILE_H) >> NO_EA
+}
+
+pub fn shift_east(board: u64) -> u64 {
+    (board & NOT_FILE_H) << EAST
+}
+
+pub fn shift_so_ea(board: u64) -> u64 {
+    (board & NOT_FILE_H) << SO_EA
+}
+
+pub fn shift_south(board: u64) -> u64 {
+    board << SOUTH
+}
+
+pub fn shift_so_we(board: u64) -> u64 {
+    (board & NOT_FILE_A) << SO_WE
+}
+
+pub fn shift_west(board: u64) -> u64 {
+    (board & NOT_FILE_A) >> WEST
+}
+
+pub fn shift_no_we(board: u64) -> u64 {
+    (board & NOT_FILE_A) >> NO_WE
+}
+
 /// Created a new board with a `1` at the specified index.
 pub fn with_bit_at(i: usize) -> u64 {
     let mut board = 0;
@@ -38,6 +77,10 @@ pub fn with_bit_at(i: usize) -> u64 {
     board
 }
 
+/// Evaluates if the bit at the specified index is set to `1`.
+///
+/// This is the canonical way to test a single bit, prefer it over comparing the
+/// result of [`get_bit`].
 pub fn is_bit_set(board: u64, i: usize) -> bool {
     get_bit(board, i) > 0
 }
@@ -64,16 +107,7 @@ pub fn has_set_bits(board: u64) -> bool {
 
 /// Calculates the number of bits set to `1`.
 pub fn count_set_bits(board: u64) -> usize {
-    let mut board = board;
-    let mut count = 0;
-
-    while board > 0 {
-        count += 1;
-
-        board &= board - 1;
-    }
-
-    count
+    board.count_ones() as usize
 }
 
 /// Returns the index of the first bit set to `1`.
@@ -85,12 +119,7 @@ pub fn get_first_set_bit(board: u64) -> Option<usize> {
         return None;
     }
 
-    let board = board as i128;
-    // Set all the bits to 1 up to the first bit.
-    let filled_up_to_first = ((board & -board) - 1) as u64;
-
-    // If the 1 bits are now counted, we can retrieve the index of it.
-    Some(count_set_bits(filled_up_to_first))
+    Some(board.trailing_zeros() as usize)
 }
 
 /// Displays a board in a human readable way.
@@ -135,8 +164,16 @@ pub fn display(board: u64) -> String {
     val
 }
 
+/// Iterates over the indices of the bits set to `1`, starting with the least
+/// significant one.
 pub struct SetBitsIter(pub u64);
 
+impl SetBitsIter {
+    pub fn new(board: u64) -> Self {
+        Self(board)
+    }
+}
+
 impl Iterator for SetBitsIter {
     type Item = usize;
 
@@ -144,7 +181,7 @@ impl Iterator for SetBitsIter {
         if let Some(i) = get_first_set_bit(self.0) {
             clear_bit(&mut self.0, i);
 
-            return Some(i as usize);
+            return Some(i);
         }
 
         None
@@ -159,24 +196,123 @@ impl TypeAliasDefault for U64PerSquare {
     }
 }
 
-pub type ColoredU64PerSquare = [U64PerSquare; 2];
+pub type ColoredU64PerSquare = ByColor<U64PerSquare>;
 
 impl TypeAliasDefault for ColoredU64PerSquare {
     fn default() -> Self {
-        [U64PerSquare::default(); 2]
+        ByColor::new(U64PerSquare::default(), U64PerSquare::default())
     }
 }
 
-impl Index<Color> for ColoredU64PerSquare {
-    type Output = U64PerSquare;
+#[cfg(test)]
+mod tests {
+    use crate::Square::*;
+
+    use super::*;
 
-    fn index(&self, index: Color) -> &Self::Output {
-        &self[index as usize]
+    #[test]
+    fn shift_does_not_wrap_files() {
+        let h4 = with_bit_at(H4.into());
+        let a4 = with_bit_at(A4.into());
+
+        assert_eq!(shift_east(h4), 0);
+        assert_eq!(shift_no_ea(h4), 0);
+        assert_eq!(shift_so_ea(h4), 0);
+        assert_eq!(shift_west(a4), 0);
+        assert_eq!(shift_no_we(a4), 0);
+        assert_eq!(shift_so_we(a4), 0);
+    }
+
+    #[test]
+    fn shift_single_bit() {
+        let e4 = with_bit_at(E4.into());
+
+        assert_eq!(shift_north(e4), with_bit_at(E5.into()));
+        assert_eq!(shift_no_ea(e4), with_bit_at(F5.into()));
+        assert_eq!(shift_east(e4), with_bit_at(F4.into()));
+        assert_eq!(shift_so_ea(e4), with_bit_at(F3.into()));
+        assert_eq!(shift_south(e4), with_bit_at(E3.into()));
+        assert_eq!(shift_so_we(e4), with_bit_at(D3.into()));
+        assert_eq!(shift_west(e4), with_bit_at(D4.into()));
+        assert_eq!(shift_no_we(e4), with_bit_at(D5.into()));
+        assert_eq!(shift_north(with_bit_at(E8.into())), 0);
+        assert_eq!(shift_south(with_bit_at(E1.into())), 0);
+    }
+
+    /// The original implementation, using Brian Kernighan's algorithm.
+    fn count_set_bits_reference(board: u64) -> usize {
+        let mut board = board;
+        let mut count = 0;
+
+        while board > 0 {
+            count += 1;
+
+            board &= board - 1;
+        }
+
+        count
+    }
+
+    /// The original implementation, counting the bits below the first set one.
+    fn get_first_set_bit_reference(board: u64) -> Option<usize> {
+        if board == 0 {
+            return None;
+        }
+
+        let board = board as i128;
+        // Set all the bits to 1 up to the first bit.
+        let filled_up_to_first = ((board & -board) - 1) as u64;
+
+        Some(count_set_bits_reference(filled_up_to_first))
+    }
+
+    fn reference_inputs() -> Vec<u64> {
+        let mut inputs = vec![0, u64::MAX, 0b1011, 0x8000_0000_0000_0001];
+        inputs.extend((0..Board::SIZE).map(with_bit_at));
+        inputs.extend((0..1000u64).map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+        inputs
+    }
+
+    #[test]
+    fn count_set_bits_matches_reference() {
+        for board in reference_inputs() {
+            assert_eq!(
+                count_set_bits(board),
+                count_set_bits_reference(board),
+                "{}",
+                board
+            );
+        }
+    }
+
+    #[test]
+    fn get_first_set_bit_matches_reference() {
+        for board in reference_inputs() {
+            assert_eq!(
+                get_first_set_bit(board),
+                get_first_set_bit_reference(board),
+                "{}",
+                board
+            );
+        }
+    }
+
+    #[test]
+    fn is_bit_set_agrees_with_get_bit() {
+        let board = 0b1011 | (1 << 63);
+
+        for i in 0..Board::SIZE {
+            assert_eq!(is_bit_set(board, i), get_bit(board, i) != 0, "{}", i);
+        }
+
+        assert!(is_bit_set(board, 0));
+        assert!(!is_bit_set(board, 2));
+        assert!(is_bit_set(board, 63));
     }
-}
 
-impl IndexMut<Color> for ColoredU64PerSquare {
-    fn index_mut(&mut self, index: Color) -> &mut Self::Output {
-        &mut self[index as usize]
+    #[test]
+    fn set_bits_iter() {
+        assert_eq!(SetBitsIter::new(0b1011).collect::<Vec<_>>(), [0, 1, 3]);
+        assert_eq!(SetBitsIter::new(0).count(), 0);
     }
 }
@@ -1,33 +1,23 @@
 use std::{
     fmt::{Debug, Display},
-    ops::{Index, IndexMut},
+    hash::{Hash, Hasher},
 };
 
 use crate::{
     bit_board::{self, NORTH, SOUTH},
-    move_generator::Move,
+    castling_rights::{CastlingRights, CastlingSide::*},
+    display_info_board,
+    evaluation::MAT_VAL,
+    fen::{self, Fen},
+    move_generator::{self, Move},
     piece,
     square::Square,
-    Color, Piece,
+    zobrist, ByColor, Color, Piece,
 };
 use Color::*;
 use Piece::*;
 
-pub type BitBoardPerColor = [u64; 2];
-
-impl Index<Color> for BitBoardPerColor {
-    type Output = u64;
-
-    fn index(&self, index: Color) -> &Self::Output {
-        &self[index as usize]
-    }
-}
-
-impl IndexMut<Color> for BitBoardPerColor {
-    fn index_mut(&mut self, index: Color) -> &mut Self::Output {
-        &mut self[index as usize]
-    }
-}
+pub type BitBoardPerColor = ByColor<u64>;
 
 pub trait BoardPos: Into<usize> + Copy {}
 impl BoardPos for usize {}
@@ -35,21 +25,36 @@ impl BoardPos for Square {}
 
 // TODO: Consider refactoring to use `i8` everywhere and save a bunch of casting.
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, Eq, Debug)]
 pub struct Board {
-    pub bishops: BitBoardPerColor,
-    pub can_black_castle_king_side: bool,
-    pub can_black_castle_queen_side: bool,
-    pub can_white_castle_king_side: bool,
-    pub can_white_castle_queen_side: bool,
-    pub en_passant_target_idx: Option<usize>,
-    pub is_whites_turn: bool,
-    pub king: BitBoardPerColor,
-    pub knights: BitBoardPerColor,
-    pub pawns: BitBoardPerColor,
+    pub(crate) bishops: BitBoardPerColor,
+    castling_rights: CastlingRights,
+    en_passant_target_idx: Option<usize>,
+    /// Number of full moves, starting at `1`, incremented after each move of
+    /// [`Black`].
+    pub full_move_number: usize,
+    /// Number of half moves since the last capture or pawn move.
+    ///
+    /// Used for the fifty-move rule.
+    pub half_move_clock: usize,
+    /// The [`Board::zobrist`] hash, updated incrementally by [`Board::set`],
+    /// [`Board::clear`], the other setters, and the move functions.
+    ///
+    /// The fields it is calculated from can only be changed through these, so it
+    /// always equals [`Board::zobrist`].
+    pub(crate) hash: u64,
+    /// The [`Board::zobrist`] hashes of all positions before the moves made using
+    /// [`Board::make_move`] (or [`Board::do_move`]).
+    ///
+    /// Used to detect repetitions.
+    pub(crate) history: Vec<u64>,
+    is_whites_turn: bool,
+    pub(crate) king: BitBoardPerColor,
+    pub(crate) knights: BitBoardPerColor,
+    pub(crate) pawns: BitBoardPerColor,
     pub promote_idx: Option<usize>,
-    pub queens: BitBoardPerColor,
-    pub rooks: BitBoardPerColor,
+    pub(crate) queens: BitBoardPerColor,
+    pub(crate) rooks: BitBoardPerColor,
 }
 
 impl Board {
@@ -59,30 +64,107 @@ impl Board {
 
     /// Combines all bit boards into a single one.
     ///
-    /// This is achieved using the `|` (bitwise or) operator.
+    /// This is the union (`|`) of [`Board::occupied_by`] for both colors.
     // TODO: This function exposes information (bitboard) that should (?) be abstracted
     // away.
     pub fn all_occupancies(&self) -> u64 {
-        // TODO: This should be replaceable by:
-        // ```
-        // self.occupancies_of(Black) & self.occupancies_of(White)
-        // ```
-        self.bishops[Color::Black]
-            | self.king[Color::Black]
-            | self.knights[Color::Black]
-            | self.pawns[Color::Black]
-            | self.queens[Color::Black]
-            | self.rooks[Color::Black]
-            | self.bishops[Color::White]
-            | self.king[Color::White]
-            | self.knights[Color::White]
-            | self.pawns[Color::White]
-            | self.queens[Color::White]
-            | self.rooks[Color::White]
+        self.occupied_by(Black) | self.occupied_by(White)
+    }
+
+    /// Get all squares attacked by the given color.
+    ///
+    /// Sliding pieces are blocked by [`Board::all_occupancies`]. Squares occupied
+    /// by pieces of the attacking color are included, as they are defended.
+    pub fn attacks_by(&self, color: Color) -> u64 {
+        let all_occ = self.all_occupancies();
+        let mut attacks = 0;
+
+        for i in bit_board::SetBitsIter(self.bishops[color]) {
+            attacks |= piece::get_bishop_attacks_for(i, all_occ);
+        }
+
+        for i in bit_board::SetBitsIter(self.king[color]) {
+            attacks |= piece::get_king_attack_mask_for(i);
+        }
+
+        for i in bit_board::SetBitsIter(self.knights[color]) {
+            attacks |= piece::get_knight_attack_mask_for(i);
+        }
+
+        for i in bit_board::SetBitsIter(self.pawns[color]) {
+            attacks |= piece::get_pawn_attacks_for(i, &color);
+        }
+
+        for i in bit_board::SetBitsIter(self.queens[color]) {
+            attacks |= piece::get_queen_attacks_for(i, all_occ);
+        }
+
+        for i in bit_board::SetBitsIter(self.rooks[color]) {
+            attacks |= piece::get_rook_attacks_for(i, all_occ);
+        }
+
+        attacks
+    }
+
+    /// Get the pieces of the given color attacking the given position.
+    pub fn attackers_of(&self, pos: impl BoardPos, color: Color) -> u64 {
+        self.attackers_to(pos.into(), self.all_occupancies()) & self.occupied_by(color)
+    }
+
+    /// Get the pieces of both colors attacking the given position, with `occ` as
+    /// the occupancy blocking sliding pieces.
+    ///
+    /// Only pieces that are part of `occ` are returned.
+    fn attackers_to(&self, pos: usize, occ: u64) -> u64 {
+        let bishops = self.bishops[Black] | self.bishops[White];
+        let queens = self.queens[Black] | self.queens[White];
+        let rooks = self.rooks[Black] | self.rooks[White];
+
+        let attackers = (piece::get_bishop_attacks_for(pos, occ) & (bishops | queens))
+            | (piece::get_rook_attacks_for(pos, occ) & (rooks | queens))
+            | (piece::get_knight_attack_mask_for(pos) & (self.knights[Black] | self.knights[White]))
+            | (piece::get_king_attack_mask_for(pos) & (self.king[Black] | self.king[White]))
+            // A pawn attacks the position, if a pawn of the opposing color on the
+            // position would attack the pawn.
+            | (piece::get_pawn_attacks_for(pos, &White) & self.pawns[Black])
+            | (piece::get_pawn_attacks_for(pos, &Black) & self.pawns[White]);
+
+        attackers & occ
+    }
+
+    pub(crate) fn bit_boards_of(&self, piece: Piece) -> &BitBoardPerColor {
+        match piece {
+            Piece::Bishop => &self.bishops,
+            Piece::King => &self.king,
+            Piece::Knight => &self.knights,
+            Piece::Pawn => &self.pawns,
+            Piece::Queen => &self.queens,
+            Piece::Rook => &self.rooks,
+        }
+    }
+
+    /// Evaluates if a draw can be claimed by the fifty-move rule, i.e. no pawn was
+    /// moved and no piece captured in the last fifty moves of each side.
+    ///
+    /// In contrast to the seventy-five-move rule, the game is not drawn
+    /// automatically (see [`Board::result`]).
+    pub fn can_claim_fifty_move_draw(&self) -> bool {
+        self.half_move_clock >= 100
+    }
+
+    /// The sides each color may still castle to.
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
     }
 
     /// Clear (remove) a piece on the specified location
     pub fn clear(&mut self, color: Color, piece: Piece, pos: impl BoardPos) {
+        let i = pos.into();
+
+        if bit_board::is_bit_set(self.bit_boards_of(piece)[color], i) {
+            self.hash ^= zobrist::piece(color, piece, i);
+        }
+
         let bit_board = match piece {
             Piece::Bishop => &mut self.bishops,
             Piece::King => &mut self.king,
@@ -92,12 +174,25 @@ impl Board {
             Piece::Rook => &mut self.rooks,
         };
 
-        bit_board::clear_bit(&mut bit_board[color], pos.into());
+        bit_board::clear_bit(&mut bit_board[color], i);
+    }
+
+    /// Get the color of the piece on the specified location, if there is one.
+    ///
+    /// Cheaper than [`Board::get`], if the kind of piece is irrelevant.
+    pub fn color_at(&self, pos: impl BoardPos) -> Option<Color> {
+        let i = pos.into();
+
+        [Black, White]
+            .into_iter()
+            .find(|color| bit_board::is_bit_set(self.occupied_by(*color), i))
     }
 
     /// Executes a given move.
     ///
-    /// Does prevent moves that would leave the king in check, and returns `false`.
+    /// Returns whether the resulting position is legal. If the move would leave
+    /// the king of the moving side in check, the move is rolled back, the board is
+    /// left exactly as it was before the call, and `false` is returned.
     ///
     /// The moves are simply executed without any additional validation. This can
     /// be especially problematic when performing special moves like en passant,
@@ -105,14 +200,401 @@ impl Board {
     // TODO: there is no reason to take ownership of `mv`. Take in a reference in
     // the future.
     pub fn do_move(&mut self, mv: Move) -> bool {
-        let board_bak = self.clone();
+        let mv_color = mv.piece_color();
+        let undo = self.make_move(mv.clone());
+
+        if self.leaves_king_in_check(mv_color) {
+            self.unmake_move(mv, undo);
+            return false;
+        }
+
+        true
+    }
+
+    /// The square a pawn can capture en passant on, if any.
+    pub fn en_passant_square(&self) -> Option<Square> {
+        self.en_passant_target_idx
+            .map(|idx| Square::try_from(idx).expect("en passant target must be on the board"))
+    }
+
+    /// Returns the board mirrored across the horizontal axis, with the colors
+    /// swapped.
+    ///
+    /// Rank 1 becomes rank 8 and vice versa, white pieces become black ones, and
+    /// the side to move, castling rights, and en passant target are adjusted
+    /// accordingly. The result is the same position from the perspective of the
+    /// other side, e.g. `evaluate(board) == -evaluate(board.flip_vertical())`.
+    ///
+    /// The history is not carried over.
+    pub fn flip_vertical(&self) -> Board {
+        // Every byte is a rank, so mirroring the ranks is reversing the bytes.
+        let flip = |bit_boards: &BitBoardPerColor| {
+            ByColor::new(
+                bit_boards[White].swap_bytes(),
+                bit_boards[Black].swap_bytes(),
+            )
+        };
+        let flip_idx = |idx: Option<usize>| idx.map(|i| i ^ 56);
+
+        let mut board = Board {
+            bishops: flip(&self.bishops),
+            castling_rights: self.castling_rights.flip_colors(),
+            en_passant_target_idx: flip_idx(self.en_passant_target_idx),
+            full_move_number: self.full_move_number,
+            half_move_clock: self.half_move_clock,
+            hash: 0,
+            history: Vec::new(),
+            is_whites_turn: !self.is_whites_turn,
+            king: flip(&self.king),
+            knights: flip(&self.knights),
+            pawns: flip(&self.pawns),
+            promote_idx: flip_idx(self.promote_idx),
+            queens: flip(&self.queens),
+            rooks: flip(&self.rooks),
+        };
+
+        board.hash = board.zobrist();
+
+        board
+    }
+
+    /// Creates a board with the given pieces placed on an otherwise empty board
+    /// (see [`Board::new_empty`]).
+    ///
+    /// Also available through [`FromIterator`], i.e. by `collect`ing the pieces.
+    pub fn from_pieces(pieces: impl IntoIterator<Item = (Square, PieceInstance)>) -> Self {
+        let mut board = Self::new_empty();
+
+        for (sq, ins) in pieces {
+            board.set_piece(sq, ins);
+        }
+
+        board
+    }
+
+    /// Get the pice ([`PieceInstance`]) on the specified location
+    ///
+    /// In case you know what piece of what color you are looking for, you should
+    /// access the bit boards directly.
+    pub fn get(&self, pos: impl BoardPos) -> Option<PieceInstance> {
+        let i = pos.into();
+
+        for color in [Color::Black, Color::White] {
+            for piece in Piece::all() {
+                if bit_board::is_bit_set(self.bit_boards_of(piece)[color], i) {
+                    return Some(PieceInstance::new(color, piece));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Evaluates if the side to move is checkmated.
+    ///
+    /// That is the case when there are no legal moves, and the king is in check.
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check() && move_generator::legal_moves(self).is_empty()
+    }
+
+    /// Evaluates if the king of the side to move is attacked.
+    ///
+    /// Returns `false` if the side to move has no king on the board.
+    pub fn is_in_check(&self) -> bool {
+        self.leaves_king_in_check(self.side_to_move())
+    }
+
+    /// Whether there is no piece on the specified location.
+    pub fn is_square_empty(&self, pos: impl BoardPos) -> bool {
+        !bit_board::is_bit_set(self.all_occupancies(), pos.into())
+    }
+
+    /// Evaluates if neither side has enough material left to checkmate.
+    ///
+    /// That is the case for:
+    /// - king versus king
+    /// - king and bishop versus king
+    /// - king and knight versus king
+    /// - king and bishop versus king and bishop, with both bishops on the same
+    ///   square color
+    pub fn is_insufficient_material(&self) -> bool {
+        let count = self.material_count();
+
+        for piece in [Pawn, Queen, Rook] {
+            if count[Black][piece as usize] + count[White][piece as usize] > 0 {
+                return false;
+            }
+        }
+
+        let knights = count[Black][Knight as usize] + count[White][Knight as usize];
+        let black_bishops = count[Black][Bishop as usize];
+        let white_bishops = count[White][Bishop as usize];
+
+        match (knights, black_bishops, white_bishops) {
+            (0, 0, 0) | (1, 0, 0) | (0, 1, 0) | (0, 0, 1) => true,
+            (0, 1, 1) => {
+                let square_color_of = |bishops: u64| {
+                    let i = bit_board::get_first_set_bit(bishops).unwrap();
+                    let file = i % Board::WIDTH;
+                    let rank = i / Board::HEIGHT;
+
+                    (file + rank) % 2
+                };
+
+                square_color_of(self.bishops[Black]) == square_color_of(self.bishops[White])
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_pos_attacked_by(&self, pos: impl BoardPos, atk_color: &Color) -> bool {
+        // Since the attacks are essentially mirrored for both sides, we just generate
+        // the opponent attacks on the square to check. If the attack includes the
+        // position if our piece, we can be attacked, and the reverse is also true.
+        //
+        // Let's say we want to see if a white pawn on E5 can attack the square D6:
+        //
+        // 8   . . . . . . . .
+        // 7   . . . . . . . .
+        // 6   . . . . . . . .
+        // 5   . . . . 1 . . .
+        // 4   . . . . . . . .
+        // 3   . . . . . . . .
+        // 2   . . . . . . . .
+        // 1   . . . . . . . .
+        //
+        //     a b c d e f g h
+        //
+        // We now simply lookup the attacks of the **opponent** on the position we
+        // want to check (pawn attacks of the square D6):
+        //
+        // 8   . . . . . . . .
+        // 7   . . . . . . . .
+        // 6   . . . . . . . .
+        // 5   . . 1 . 1 . . .
+        // 4   . . . . . . . .
+        // 3   . . . . . . . .
+        // 2   . . . . . . . .
+        // 1   . . . . . . . .
+        //
+        //     a b c d e f g h
+        //
+        // We can see that the bit on E5 is set on both boards, thus the square
+        // D6 can be attacked by the white pawn on E5.
+
+        let all_occ = self.all_occupancies();
+        let def_color = atk_color.opposing();
+
+        if bit_board::has_set_bits(
+            piece::get_bishop_attacks_for(pos, all_occ) & self.bishops[*atk_color],
+        ) {
+            return true;
+        }
+
+        if bit_board::has_set_bits(piece::get_king_attack_mask_for(pos) & self.king[*atk_color]) {
+            return true;
+        }
+
+        if bit_board::has_set_bits(
+            piece::get_knight_attack_mask_for(pos) & self.knights[*atk_color],
+        ) {
+            return true;
+        }
+
+        if bit_board::has_set_bits(
+            piece::get_pawn_attacks_for(pos, &def_color) & self.pawns[*atk_color],
+        ) {
+            return true;
+        }
+
+        if bit_board::has_set_bits(
+            piece::get_queen_attacks_for(pos, all_occ) & self.queens[*atk_color],
+        ) {
+            return true;
+        }
+
+        if bit_board::has_set_bits(
+            piece::get_rook_attacks_for(pos, all_occ) & self.rooks[*atk_color],
+        ) {
+            return true;
+        }
+
+        false
+    }
+
+    /// Evaluates if the move could be generated in this position, without checking
+    /// whether it leaves the own king in check.
+    ///
+    /// This is meant to cheaply validate moves which were not generated for this
+    /// exact position, like ones from a transposition or killer move table, before
+    /// passing them to [`Board::do_move`]. The move has to be of the side to move,
+    /// its piece has to be on the source, and the destination has to be reachable
+    /// by it. Special moves (castles, double pushes, en passant, and promotions)
+    /// have to be flagged as such, and are only accepted if the board allows them.
+    pub fn is_pseudo_legal(&self, mv: &Move) -> bool {
+        let color = mv.piece_color();
+        let piece = mv.piece();
+        let src = mv.src();
+        let dst = mv.dst();
+
+        if color != self.side_to_move()
+            || src >= Board::SIZE
+            || dst >= Board::SIZE
+            || self.get(src) != Some(PieceInstance::new(color, piece))
+            || bit_board::is_bit_set(self.occupied_by(color), dst)
+        {
+            return false;
+        }
+
+        let all_occ = self.all_occupancies();
+
+        if mv.is_castle() {
+            let (side, clear, not_atk): (_, &[Square], &[Square]) = match (color, dst) {
+                (Black, 2) => (
+                    QueenSide,
+                    &[Square::B8, Square::C8, Square::D8],
+                    &[Square::C8, Square::D8, Square::E8],
+                ),
+                (Black, 6) => (
+                    KingSide,
+                    &[Square::F8, Square::G8],
+                    &[Square::E8, Square::F8, Square::G8],
+                ),
+                (White, 58) => (
+                    QueenSide,
+                    &[Square::B1, Square::C1, Square::D1],
+                    &[Square::C1, Square::D1, Square::E1],
+                ),
+                (White, 62) => (
+                    KingSide,
+                    &[Square::F1, Square::G1],
+                    &[Square::E1, Square::F1, Square::G1],
+                ),
+                _ => return false,
+            };
+
+            let king_src = match color {
+                Black => Square::E8,
+                White => Square::E1,
+            };
+
+            return piece == King
+                && src == usize::from(king_src)
+                && self.castling_rights.has(color, side)
+                && clear.iter().all(|&sq| self.is_square_empty(sq))
+                && !not_atk
+                    .iter()
+                    .any(|&sq| self.is_pos_attacked_by(sq, &color.opposing()));
+        }
+
+        if piece != Pawn {
+            let attacks = match piece {
+                Bishop => piece::get_bishop_attacks_for(src, all_occ),
+                King => piece::get_king_attack_mask_for(src),
+                Knight => piece::get_knight_attack_mask_for(src),
+                Queen => piece::get_queen_attacks_for(src, all_occ),
+                Rook => piece::get_rook_attacks_for(src, all_occ),
+                Pawn => unreachable!(),
+            };
+
+            return mv.prom_to().is_none()
+                && !mv.is_dbl_push()
+                && !mv.is_en_passant()
+                && bit_board::is_bit_set(attacks, dst);
+        }
+
+        let (push_dst, start_rank, prom_rank) = match color {
+            Black => (src + SOUTH, 1, Board::HEIGHT - 1),
+            White => (src.wrapping_sub(NORTH), Board::HEIGHT - 2, 0),
+        };
+        let is_prom = dst / Board::WIDTH == prom_rank;
+
+        if is_prom != mv.prom_to().is_some() || matches!(mv.prom_to(), Some(King | Pawn)) {
+            return false;
+        }
+
+        let attacks = piece::get_pawn_attacks_for(src, &color);
+
+        if mv.is_en_passant() {
+            return self.en_passant_target_idx == Some(dst) && bit_board::is_bit_set(attacks, dst);
+        }
+
+        if mv.is_dbl_push() {
+            let dbl_push_dst = match color {
+                Black => push_dst + SOUTH,
+                White => push_dst.wrapping_sub(NORTH),
+            };
+
+            return src / Board::WIDTH == start_rank
+                && dst == dbl_push_dst
+                && self.is_square_empty(push_dst)
+                && self.is_square_empty(dst);
+        }
+
+        match bit_board::is_bit_set(attacks, dst) {
+            true => bit_board::is_bit_set(self.occupied_by(color.opposing()), dst),
+            false => dst == push_dst && self.is_square_empty(dst),
+        }
+    }
+
+    /// Evaluates if the side to move is stalemated.
+    ///
+    /// That is the case when there are no legal moves, but the king is not in
+    /// check. Boards without a king for the side to move are never considered
+    /// stalemated.
+    pub fn is_stalemate(&self) -> bool {
+        let color = self.side_to_move();
+
+        bit_board::has_set_bits(self.king[color])
+            && !self.is_in_check()
+            && move_generator::legal_moves(self).is_empty()
+    }
+
+    /// Get the square of the king of the given color.
+    ///
+    /// Returns `None` if there is no king of that color on the board.
+    pub fn king_square(&self, color: Color) -> Option<Square> {
+        bit_board::get_first_set_bit(self.king[color]).map(|i| Square::ALL[i])
+    }
+
+    /// Whether the king of the given color is attacked, i.e. a move of that color
+    /// that led to this position was illegal.
+    ///
+    /// Boards without a king (e.g. constructed for tests) have no king that could
+    /// be left in check, so this returns `false` for them.
+    pub fn leaves_king_in_check(&self, color: Color) -> bool {
+        self.king_square(color)
+            .is_some_and(|king_pos| self.is_pos_attacked_by(king_pos, &color.opposing()))
+    }
 
+    /// Executes a given move, and returns the information required to take it
+    /// back again using [`Board::unmake_move`].
+    ///
+    /// Other than [`Board::do_move`], this function does not check if the move
+    /// leaves the king in check. The same caveats regarding validation apply.
+    pub fn make_move(&mut self, mv: Move) -> Undo {
         let mv_color = mv.piece_color();
         let opp_color = mv_color.opposing();
         let mv_src = mv.src();
         let mv_dst = mv.dst();
         let mv_piece = mv.piece();
 
+        let undo = Undo {
+            captured: match mv.is_en_passant() {
+                true => Some(Pawn),
+                false => self
+                    .get(mv_dst)
+                    .filter(|ins| ins.color == opp_color)
+                    .map(|ins| ins.piece),
+            },
+            castling_rights: self.castling_rights,
+            en_passant_target_idx: self.en_passant_target_idx,
+            full_move_number: self.full_move_number,
+            half_move_clock: self.half_move_clock,
+        };
+
+        self.history.push(self.hash);
+
         // Move the piece
         self.clear(mv_color, mv_piece, mv_src);
         self.set(mv_color, mv_piece, mv_dst);
@@ -120,36 +602,26 @@ impl Board {
         // (Potentially) clear castling rights
         if mv_piece == Rook {
             match mv_src {
-                0  /* Square::A8 */ => self.can_black_castle_queen_side = false,
-                7  /* Square::H8 */ => self.can_black_castle_king_side = false,
-                56 /* Square::A1 */ => self.can_white_castle_queen_side = false,
-                63 /* Square::H1 */ => self.can_white_castle_king_side = false,
+                0  /* Square::A8 */ => self.castling_rights.remove(Black, QueenSide),
+                7  /* Square::H8 */ => self.castling_rights.remove(Black, KingSide),
+                56 /* Square::A1 */ => self.castling_rights.remove(White, QueenSide),
+                63 /* Square::H1 */ => self.castling_rights.remove(White, KingSide),
                 _ => (),
             };
         } else if mv_piece == King {
-            if mv_color == Black {
-                self.can_black_castle_king_side = false;
-                self.can_black_castle_queen_side = false;
-            } else {
-                self.can_white_castle_king_side = false;
-                self.can_white_castle_queen_side = false;
-            }
+            self.castling_rights.clear_color(mv_color);
         }
 
         // Remove (potentially) captured piece on the destination position
-        for piece in [Bishop, King, Knight, Pawn, Queen, Rook] {
-            self.clear(opp_color, piece, mv_dst);
+        if !mv.is_en_passant() {
+            if let Some(captured) = undo.captured {
+                self.clear(opp_color, captured, mv_dst);
+            }
         }
 
         // Handle castle
         if mv.is_castle() {
-            let (rook_src, rook_dst) = match mv_dst {
-                2  /* Square::C8 */ => (Square::A8, Square::D8),
-                6  /* Square::G8 */ => (Square::H8, Square::F8),
-                58 /* Square::C1 */ => (Square::A1, Square::D1),
-                62 /* Square::G1 */ => (Square::H1, Square::F1),
-                _ => panic!("invalid castle destination '{:?}'", Square::try_from(mv_dst)),
-            };
+            let (rook_src, rook_dst) = castle_rook_move(mv_dst);
 
             self.clear(mv_color, Rook, rook_src);
             self.set(mv_color, Rook, rook_dst);
@@ -157,12 +629,7 @@ impl Board {
 
         // Handle en passant
         if mv.is_en_passant() {
-            let en_pass_cap_idx = match mv_color {
-                White => mv_dst + SOUTH,
-                Black => mv_dst - NORTH,
-            };
-
-            self.clear(opp_color, Pawn, en_pass_cap_idx);
+            self.clear(opp_color, Pawn, en_passant_capture_idx(mv_color, mv_dst));
         }
 
         // En passant is only valid for the next turn immediately after, thus
@@ -185,311 +652,1529 @@ impl Board {
 
         // Remove the castling rights if the rooks are captured.
         match mv_dst {
-            0  /* Square::A8 */ => self.can_black_castle_queen_side = false,
-            7  /* Square::H8 */ => self.can_black_castle_king_side = false,
-            56 /* Square::A1 */ => self.can_white_castle_queen_side = false,
-            63 /* Square::H1 */ => self.can_white_castle_king_side = false,
+            0  /* Square::A8 */ => self.castling_rights.remove(Black, QueenSide),
+            7  /* Square::H8 */ => self.castling_rights.remove(Black, KingSide),
+            56 /* Square::A1 */ => self.castling_rights.remove(White, QueenSide),
+            63 /* Square::H1 */ => self.castling_rights.remove(White, KingSide),
             _ => (),
         }
 
+        // The clock is reset by any pawn move or capture.
+        if mv_piece == Pawn || undo.captured.is_some() {
+            self.half_move_clock = 0;
+        } else {
+            self.half_move_clock += 1;
+        }
+
+        if mv_color == Black {
+            self.full_move_number += 1;
+        }
+
         self.is_whites_turn = !self.is_whites_turn;
 
-        // Check if the king is attacked on this new board constellation. If this
-        // is the case, the move was not legal, and the board is reverted.
-        let king_pos =
-            Square::try_from(bit_board::get_first_set_bit(self.king[mv_color]).unwrap()).unwrap();
-        let is_king_attacked = self.is_pos_attacked_by(king_pos, &opp_color);
+        // The piece keys are already updated by `set` and `clear`.
+        self.hash ^= zobrist::white_to_move()
+            ^ zobrist::castling_rights(undo.castling_rights)
+            ^ zobrist::castling_rights(self.castling_rights)
+            ^ zobrist::en_passant(undo.en_passant_target_idx)
+            ^ zobrist::en_passant(self.en_passant_target_idx);
+
+        debug_assert_eq!(
+            self.hash,
+            self.zobrist(),
+            "incremental hash diverged from the recomputed one"
+        );
 
-        if is_king_attacked {
-            *self = board_bak;
-            return false;
+        undo
+    }
+
+    /// Counts the pieces on the board.
+    ///
+    /// The counts are indexed by the color, and then the piece (e.g.
+    /// `count[White][Pawn as usize]`).
+    pub fn material_count(&self) -> ByColor<[u8; 6]> {
+        let mut count = ByColor::<[u8; 6]>::default();
+
+        for piece in Piece::all() {
+            for color in [Black, White] {
+                count[color][piece as usize] =
+                    bit_board::count_set_bits(self.bit_boards_of(piece)[color]) as u8;
+            }
         }
 
-        true
+        count
     }
 
-    /// Get the pice ([`PieceInstance`]) on the specified location
+    /// Passes the turn to the opponent, without moving a piece.
     ///
-    /// In case you know what piece of what color you are looking for, you should
-    /// access the bit boards directly.
-    pub fn get(&self, pos: impl BoardPos) -> Option<PieceInstance> {
-        let i = pos.into();
+    /// Returns the previous en passant target, which is cleared, as it has to be
+    /// passed to [`Board::unmake_null_move`]. Used for null move pruning in the
+    /// search.
+    ///
+    /// A null move must never be made while in check, as it would allow the
+    /// opponent to capture the king.
+    pub fn make_null_move(&mut self) -> Option<usize> {
+        let prev_ep = self.en_passant_target_idx.take();
 
-        for color in [Color::Black, Color::White] {
-            if bit_board::is_bit_set(self.bishops[color], i) {
-                return Some(PieceInstance::new(color, Piece::Bishop));
+        self.is_whites_turn = !self.is_whites_turn;
+        self.hash ^= zobrist::white_to_move() ^ zobrist::en_passant(prev_ep);
+
+        prev_ep
+    }
+
+    /// Evaluates if the current position occurred (at least) three times.
+    ///
+    /// Only positions in the [`Board::history`] are considered. Positions before
+    /// the last capture or pawn move can never repeat, and are thus skipped.
+    pub fn is_threefold_repetition(&self) -> bool {
+        let key = self.hash;
+        let occurrences = self
+            .history
+            .iter()
+            .rev()
+            .take(self.half_move_clock)
+            .filter(|k| **k == key)
+            .count();
+
+        // The current position counts as an occurrence as well.
+        occurrences + 1 >= 3
+    }
+
+    pub fn new_empty() -> Self {
+        Self {
+            bishops: BitBoardPerColor::default(),
+            castling_rights: CastlingRights::NONE,
+            en_passant_target_idx: None,
+            full_move_number: 1,
+            half_move_clock: 0,
+            // Only white's turn contributes to the hash of an empty board.
+            hash: zobrist::white_to_move(),
+            history: Vec::new(),
+            is_whites_turn: true,
+            king: BitBoardPerColor::default(),
+            knights: BitBoardPerColor::default(),
+            pawns: BitBoardPerColor::default(),
+            promote_idx: None,
+            queens: BitBoardPerColor::default(),
+            rooks: BitBoardPerColor::default(),
+        }
+    }
+
+    pub fn new_with_standard_formation() -> Self {
+        Self::from_fen(fen::STARTING_FEN).expect("the starting position FEN is valid")
+    }
+
+    /// Same as [`Board::occupied_by`].
+    pub fn occupancies_of(&self, color: Color) -> u64 {
+        self.occupied_by(color)
+    }
+
+    /// Get the occupied squares of a certain color.
+    // TODO: This function exposes information (bitboard) that should (?) be abstracted
+    // away.
+    pub fn occupied_by(&self, color: Color) -> u64 {
+        self.bishops[color]
+            | self.king[color]
+            | self.knights[color]
+            | self.pawns[color]
+            | self.queens[color]
+            | self.rooks[color]
+    }
+
+    /// Get the bit board of the given piece and color.
+    pub fn piece_bitboard(&self, color: Color, piece: Piece) -> u64 {
+        self.bit_boards_of(piece)[color]
+    }
+
+    /// Iterates over all occupied squares, together with the piece on them.
+    ///
+    /// Every bit board is walked once, which is cheaper than calling
+    /// [`Board::get`] for each square. The order is by piece, then color, then
+    /// square index.
+    pub fn pieces(&self) -> impl Iterator<Item = (Square, PieceInstance)> + '_ {
+        Piece::all().into_iter().flat_map(move |piece| {
+            let bit_boards = self.bit_boards_of(piece);
+
+            [Black, White].into_iter().flat_map(move |color| {
+                bit_board::SetBitsIter(bit_boards[color])
+                    .map(move |i| (Square::ALL[i], PieceInstance::new(color, piece)))
+            })
+        })
+    }
+
+    /// The pieces of [`Board::pieces`], indexed by their square.
+    fn piece_grid(&self) -> [Option<PieceInstance>; Board::SIZE] {
+        let mut grid = std::array::from_fn(|_| None);
+
+        for (sq, ins) in self.pieces() {
+            grid[sq as usize] = Some(ins);
+        }
+
+        grid
+    }
+
+    /// Get the pieces of the given color that are absolutely pinned to their king.
+    ///
+    /// A piece is pinned if it is the only piece between its king and an opposing
+    /// bishop, rook, or queen attacking along that line. Boards without a king of
+    /// the given color have no pinned pieces.
+    ///
+    /// En passant "pseudo-pins" are not covered: if capturing en passant removes
+    /// both pawns from the king's rank, exposing it to a rook or queen, neither pawn
+    /// is reported as pinned.
+    pub fn pinned_pieces(&self, color: Color) -> u64 {
+        let Some(king_idx) = self.king_square(color).map(usize::from) else {
+            return 0;
+        };
+
+        let opp_color = color.opposing();
+        let fren_occ = self.occupied_by(color);
+        let opp_occ = self.occupied_by(opp_color);
+        let king_bit = bit_board::with_bit_at(king_idx);
+
+        let mut pinned = 0;
+
+        let bishop_sliders = self.bishops[opp_color] | self.queens[opp_color];
+        let rook_sliders = self.rooks[opp_color] | self.queens[opp_color];
+        let get_bishop_attacks: fn(usize, u64) -> u64 = piece::get_bishop_attacks_for;
+        let get_rook_attacks: fn(usize, u64) -> u64 = piece::get_rook_attacks_for;
+
+        for (get_attacks, sliders) in [
+            (get_bishop_attacks, bishop_sliders),
+            (get_rook_attacks, rook_sliders),
+        ] {
+            // Only the opponent pieces block here, so every slider found is aligned
+            // with the king, and only has friendly pieces (if any) in between.
+            let snipers = get_attacks(king_idx, opp_occ) & sliders;
+
+            for sniper_idx in bit_board::SetBitsIter(snipers) {
+                let between = get_attacks(king_idx, bit_board::with_bit_at(sniper_idx))
+                    & get_attacks(sniper_idx, king_bit);
+                let blockers = between & fren_occ;
+
+                if bit_board::count_set_bits(blockers) == 1 {
+                    pinned |= blockers;
+                }
+            }
+        }
+
+        pinned
+    }
+
+    /// Calculates a key identifying the position, which is its [`Board::zobrist`]
+    /// hash.
+    pub fn position_key(&self) -> u64 {
+        self.zobrist()
+    }
+
+    /// Evaluates the outcome of the game in the current position.
+    ///
+    /// The game is drawn by stalemate, insufficient material, or the
+    /// seventy-five-move rule (a half move clock of at least `150`). Only draws
+    /// that happen automatically are reported, a draw by the fifty-move rule has
+    /// to be claimed (see [`Board::can_claim_fifty_move_draw`]).
+    pub fn result(&self) -> GameResult {
+        if self.is_checkmate() {
+            return match self.side_to_move() {
+                Black => GameResult::WhiteWins,
+                White => GameResult::BlackWins,
+            };
+        }
+
+        if self.is_stalemate() || self.is_insufficient_material() || self.half_move_clock >= 150 {
+            return GameResult::Draw;
+        }
+
+        GameResult::Ongoing
+    }
+
+    /// Static exchange evaluation of the given capture.
+    ///
+    /// Both sides keep capturing on the destination of `mv`, always with their
+    /// least valuable attacker, and may stop whenever continuing would lose
+    /// material. The result is the material ([`MAT_VAL`]) won by the side making
+    /// the move, which is negative if the capture loses material. Attackers behind
+    /// other attackers (e.g. two rooks on a file) are taken into account.
+    ///
+    /// Pins are ignored, and the king only captures if the square is not defended
+    /// anymore.
+    pub fn see(&self, mv: &Move) -> i32 {
+        // Attackers are tried in this order, the king has to be last.
+        const ORDER: [Piece; 6] = [Pawn, Knight, Bishop, Rook, Queen, King];
+
+        let dst = mv.dst();
+        let mut occ = self.all_occupancies() & !bit_board::with_bit_at(mv.src());
+        let mut gains = Vec::with_capacity(32);
+
+        let captured = if mv.is_en_passant() {
+            let captured_idx = en_passant_capture_idx(mv.piece_color(), dst);
+            bit_board::clear_bit(&mut occ, captured_idx);
+            Some(Pawn)
+        } else {
+            self.get(dst).map(|ins| ins.piece)
+        };
+
+        let mut gain = captured.map_or(0, |piece| MAT_VAL[piece] as i32);
+        // The piece that is standing on the destination, and can be captured next.
+        let mut on_dst = mv.piece();
+
+        if let Some(prom_to) = mv.prom_to() {
+            gain += MAT_VAL[prom_to] as i32 - MAT_VAL[Pawn] as i32;
+            on_dst = prom_to;
+        }
+
+        gains.push(gain);
+
+        let mut color = mv.piece_color().opposing();
+
+        loop {
+            let attackers = self.attackers_to(dst, occ);
+            let own_attackers = attackers & self.occupied_by(color);
+
+            let Some((piece, attacker)) = ORDER.into_iter().find_map(|piece| {
+                let bits = own_attackers & self.bit_boards_of(piece)[color];
+                bit_board::get_first_set_bit(bits).map(|i| (piece, i))
+            }) else {
+                break;
+            };
+
+            // The king can't capture a defended piece.
+            if piece == King && attackers & self.occupied_by(color.opposing()) != 0 {
+                break;
+            }
+
+            gains.push(MAT_VAL[on_dst] as i32 - gains[gains.len() - 1]);
+            bit_board::clear_bit(&mut occ, attacker);
+            on_dst = piece;
+            color = color.opposing();
+        }
+
+        // Every side may decide to not capture, if that is better.
+        while gains.len() > 1 {
+            let last = gains.pop().unwrap();
+            let prev = gains.last_mut().unwrap();
+
+            *prev = -(-*prev).max(last);
+        }
+
+        gains[0]
+    }
+
+    /// Set (add) a piece on the specified location
+    // TODO: convert parameters to references
+    pub fn set(&mut self, color: Color, piece: Piece, pos: impl BoardPos) {
+        let i = pos.into();
+
+        if !bit_board::is_bit_set(self.bit_boards_of(piece)[color], i) {
+            self.hash ^= zobrist::piece(color, piece, i);
+        }
+
+        match piece {
+            Piece::Bishop => bit_board::set_bit(&mut self.bishops[color], i),
+            Piece::King => bit_board::set_bit(&mut self.king[color], i),
+            Piece::Knight => bit_board::set_bit(&mut self.knights[color], i),
+            Piece::Pawn => bit_board::set_bit(&mut self.pawns[color], i),
+            Piece::Queen => bit_board::set_bit(&mut self.queens[color], i),
+            Piece::Rook => bit_board::set_bit(&mut self.rooks[color], i),
+        }
+    }
+
+    /// Sets the sides each color may castle to, keeping the hash in sync.
+    pub fn set_castling_rights(&mut self, castling_rights: CastlingRights) {
+        self.hash ^= zobrist::castling_rights(self.castling_rights)
+            ^ zobrist::castling_rights(castling_rights);
+        self.castling_rights = castling_rights;
+    }
+
+    /// Sets the square a pawn can capture en passant on, keeping the hash in sync.
+    pub fn set_en_passant_square(&mut self, sq: Option<Square>) {
+        let idx = sq.map(usize::from);
+
+        self.hash ^= zobrist::en_passant(self.en_passant_target_idx) ^ zobrist::en_passant(idx);
+        self.en_passant_target_idx = idx;
+    }
+
+    /// Sets the side to move, keeping the hash in sync.
+    pub fn set_side_to_move(&mut self, color: Color) {
+        let is_whites_turn = color == White;
+
+        if self.is_whites_turn != is_whites_turn {
+            self.is_whites_turn = is_whites_turn;
+            self.hash ^= zobrist::white_to_move();
+        }
+    }
+
+    /// Same as [`Board::set`], but taking the position first, and the piece as a
+    /// whole, which can't be mixed up.
+    pub fn set_piece(&mut self, pos: impl BoardPos, ins: PieceInstance) {
+        self.set(ins.color, ins.piece, pos);
+    }
+
+    /// The color whose turn it is.
+    pub fn side_to_move(&self) -> Color {
+        match self.is_whites_turn {
+            true => White,
+            false => Black,
+        }
+    }
+
+    /// Alias of [`Board::new_with_standard_formation`].
+    pub fn starting_position() -> Self {
+        Self::new_with_standard_formation()
+    }
+
+    /// Renders the board with ANSI escape sequences, for display in a terminal.
+    ///
+    /// The squares are drawn with a light or dark background, the pieces in
+    /// white or black. Use [`Board::to_ascii`] or the [`Display`] implementation
+    /// for output that is not written to a terminal.
+    pub fn to_ansi(&self) -> String {
+        const FG_BLACK: &str = "\u{001b}[1;30m";
+        const FG_WHITE: &str = "\u{001b}[1;97m";
+
+        let grid = self.piece_grid();
+
+        display_info_board::ansi_grid(|i| {
+            let content = match &grid[i] {
+                Some(ins) => format!(
+                    "{} {} ",
+                    if ins.color == White {
+                        FG_WHITE
+                    } else {
+                        FG_BLACK
+                    },
+                    ins.piece.to_char()
+                ),
+                None => "   ".to_owned(),
+            };
+
+            (None, content)
+        })
+    }
+
+    /// Renders the board as plain text, without any special characters.
+    ///
+    /// White pieces are uppercase and black pieces lowercase letters, empty squares
+    /// are `.`. The ranks and files are labeled like in the [`Display`]
+    /// implementation.
+    pub fn to_ascii(&self) -> String {
+        let mut val = String::new();
+
+        for (i, ins) in self.piece_grid().iter().enumerate() {
+            let file = i % Board::HEIGHT;
+            let rank = i / Board::HEIGHT;
+
+            if file == 0 {
+                val += &format!("{}  ", Board::HEIGHT - rank);
+            }
+
+            let sym = match ins {
+                Some(ins) if ins.color == White => ins.piece.to_char(),
+                Some(ins) => ins.piece.to_char().to_ascii_lowercase(),
+                None => '.',
+            };
+
+            val += &format!(" {}", sym);
+
+            if file == 7 {
+                val += "\n";
             }
-            if bit_board::is_bit_set(self.king[color], i) {
-                return Some(PieceInstance::new(color, Piece::King));
+        }
+
+        val += "\n    a b c d e f g h";
+
+        val
+    }
+
+    /// Executes the given move, if it is legal.
+    ///
+    /// Other than [`Board::do_move`], the move is validated against the
+    /// [`move_generator::legal_moves`] first. A move is matched by its source,
+    /// destination, and promotion, the remaining information (e.g. whether it is
+    /// a castle) is taken from the generated move. If the move is not legal, an
+    /// error is returned and the board is left untouched.
+    pub fn try_do_move(&mut self, mv: Move) -> Result<(), String> {
+        let legal_mv = move_generator::legal_moves(self)
+            .into_iter()
+            .find(|legal_mv| {
+                legal_mv.src() == mv.src()
+                    && legal_mv.dst() == mv.dst()
+                    && legal_mv.prom_to() == mv.prom_to()
+            });
+
+        match legal_mv {
+            Some(legal_mv) => {
+                self.do_move(legal_mv);
+                Ok(())
             }
-            if bit_board::is_bit_set(self.knights[color], i) {
-                return Some(PieceInstance::new(color, Piece::Knight));
+            None => Err(format!("the move '{}' is not legal in this position", mv)),
+        }
+    }
+
+    /// Takes back a move previously executed by [`Board::make_move`].
+    ///
+    /// `mv` and `undo` have to be the move passed to, and the value returned by
+    /// [`Board::make_move`]. The board is restored to exactly the state it was
+    /// in before the move was made.
+    pub fn unmake_move(&mut self, mv: Move, undo: Undo) {
+        let mv_color = mv.piece_color();
+        let opp_color = mv_color.opposing();
+        let mv_src = mv.src();
+        let mv_dst = mv.dst();
+
+        self.is_whites_turn = !self.is_whites_turn;
+
+        // Move the piece back (as a pawn in case of a promotion)
+        self.clear(mv_color, mv.prom_to().unwrap_or(mv.piece()), mv_dst);
+        self.set(mv_color, mv.piece(), mv_src);
+
+        // Restore the captured piece
+        if let Some(captured) = undo.captured {
+            match mv.is_en_passant() {
+                true => self.set(opp_color, Pawn, en_passant_capture_idx(mv_color, mv_dst)),
+                false => self.set(opp_color, captured, mv_dst),
             }
-            if bit_board::is_bit_set(self.pawns[color], i) {
-                return Some(PieceInstance::new(color, Piece::Pawn));
+        }
+
+        // Move the rook back
+        if mv.is_castle() {
+            let (rook_src, rook_dst) = castle_rook_move(mv_dst);
+
+            self.clear(mv_color, Rook, rook_dst);
+            self.set(mv_color, Rook, rook_src);
+        }
+
+        self.castling_rights = undo.castling_rights;
+        self.en_passant_target_idx = undo.en_passant_target_idx;
+        self.full_move_number = undo.full_move_number;
+        self.half_move_clock = undo.half_move_clock;
+        // The position before the move is always the last one in the history.
+        if let Some(hash) = self.history.pop() {
+            self.hash = hash;
+        }
+    }
+
+    /// Takes back a null move made by [`Board::make_null_move`].
+    ///
+    /// `prev_ep` has to be the value returned by [`Board::make_null_move`].
+    pub fn unmake_null_move(&mut self, prev_ep: Option<usize>) {
+        self.en_passant_target_idx = prev_ep;
+        self.is_whites_turn = !self.is_whites_turn;
+        self.hash ^= zobrist::white_to_move() ^ zobrist::en_passant(prev_ep);
+    }
+
+    /// Calculates the Zobrist hash of the position.
+    ///
+    /// Takes into account the piece placement, the side to move, the castling
+    /// rights, and the en passant file. The move clocks are ignored, meaning
+    /// identical positions hash identically, regardless of how they were reached.
+    pub fn zobrist(&self) -> u64 {
+        let mut key = 0;
+
+        for piece in Piece::all() {
+            for color in [Black, White] {
+                for pos in bit_board::SetBitsIter(self.bit_boards_of(piece)[color]) {
+                    key ^= zobrist::piece(color, piece, pos);
+                }
             }
-            if bit_board::is_bit_set(self.queens[color], i) {
-                return Some(PieceInstance::new(color, Piece::Queen));
+        }
+
+        if self.is_whites_turn {
+            key ^= zobrist::white_to_move();
+        }
+
+        key ^= zobrist::castling_rights(self.castling_rights);
+        key ^= zobrist::en_passant(self.en_passant_target_idx);
+
+        key
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new_with_standard_formation()
+    }
+}
+
+impl FromIterator<(Square, PieceInstance)> for Board {
+    fn from_iter<T: IntoIterator<Item = (Square, PieceInstance)>>(iter: T) -> Self {
+        Self::from_pieces(iter)
+    }
+}
+
+impl PartialEq for Board {
+    /// Compares the positions, ignoring the hash derived from them and the history
+    /// of how they were reached.
+    fn eq(&self, other: &Self) -> bool {
+        self.bishops == other.bishops
+            && self.castling_rights == other.castling_rights
+            && self.en_passant_target_idx == other.en_passant_target_idx
+            && self.full_move_number == other.full_move_number
+            && self.half_move_clock == other.half_move_clock
+            && self.is_whites_turn == other.is_whites_turn
+            && self.king == other.king
+            && self.knights == other.knights
+            && self.pawns == other.pawns
+            && self.promote_idx == other.promote_idx
+            && self.queens == other.queens
+            && self.rooks == other.rooks
+    }
+}
+
+/// Hashes the [`Board::zobrist`] key, which covers the position (pieces, side to
+/// move, castling rights, and en passant target). Every field it is calculated from
+/// is also compared by [`PartialEq`], so equal boards always hash equally.
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist());
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut val = String::new();
+
+        for (i, ins) in self.piece_grid().iter().enumerate() {
+            let file = i % Board::HEIGHT;
+            let rank = i / Board::HEIGHT;
+
+            if file == 0 {
+                val += &format!("{}  ", Board::HEIGHT - rank);
             }
-            if bit_board::is_bit_set(self.rooks[color], i) {
-                return Some(PieceInstance::new(color, Piece::Rook));
+
+            let sym = match ins {
+                Some(ins) => ins.piece.symbol(ins.color).to_owned(),
+                None => ".".to_owned(),
+            };
+
+            val += &format!(" {}", sym);
+
+            if file == 7 {
+                val += "\n";
             }
         }
 
-        None
+        val += "\n    a b c d e f g h";
+
+        val += "\n    side to move: ";
+        val += if self.is_whites_turn {
+            "white"
+        } else {
+            "black"
+        };
+
+        val += "\n    en passant target: ";
+        val += &self
+            .en_passant_target_idx
+            .map(|i| format!("{:?}", Square::try_from(i).unwrap()))
+            .unwrap_or_else(|| "<None>".to_owned());
+
+        write!(f, "{}", val)
+    }
+}
+
+/// Boards are serialized as their FEN (see [`Fen`]), which is a lot more compact
+/// than the individual bit boards.
+///
+/// The move history is not part of a FEN and is therefore lost, meaning that
+/// repetitions before the serialized position are not detected.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.get_fen())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fen = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+
+        Board::from_fen(&fen).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The outcome of a game, see [`Board::result`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    BlackWins,
+    Draw,
+    Ongoing,
+    WhiteWins,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PieceInstance {
+    pub color: Color,
+    pub piece: Piece,
+}
+
+impl PieceInstance {
+    pub fn new(color: Color, piece: Piece) -> Self {
+        Self { piece, color }
+    }
+}
+
+/// Returns the source and destination of the rook for a castle with the given
+/// king destination.
+fn castle_rook_move(king_dst: usize) -> (Square, Square) {
+    match king_dst {
+        2  /* Square::C8 */ => (Square::A8, Square::D8),
+        6  /* Square::G8 */ => (Square::H8, Square::F8),
+        58 /* Square::C1 */ => (Square::A1, Square::D1),
+        62 /* Square::G1 */ => (Square::H1, Square::F1),
+        _ => panic!("invalid castle destination '{:?}'", Square::try_from(king_dst)),
+    }
+}
+
+/// Returns the position of the pawn that is captured by an en passant move to
+/// the given destination.
+fn en_passant_capture_idx(mv_color: Color, mv_dst: usize) -> usize {
+    match mv_color {
+        White => mv_dst + SOUTH,
+        Black => mv_dst - NORTH,
+    }
+}
+
+/// Information required to take back a move, see [`Board::make_move`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Undo {
+    captured: Option<Piece>,
+    castling_rights: CastlingRights,
+    en_passant_target_idx: Option<usize>,
+    full_move_number: usize,
+    half_move_clock: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        bit_board::{NORTH, SOUTH},
+        testing_utils,
+    };
+
+    use super::*;
+
+    use Square::*;
+
+    #[test]
+    fn attackers_of_two_attackers() {
+        let board = Board::from_fen("4k3/8/2np4/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.attackers_of(E5, Black),
+            bit_board::with_bit_at(C6.into()) | bit_board::with_bit_at(D6.into())
+        );
+        assert_eq!(board.attackers_of(E5, White), 0);
+    }
+
+    #[test]
+    fn attackers_of_blocked() {
+        let board = Board::from_fen("3rk3/b7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.attackers_of(D4, Black),
+            bit_board::with_bit_at(D8.into()) | bit_board::with_bit_at(A7.into())
+        );
+
+        let board = Board::from_fen("3rk3/b7/8/2P5/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.attackers_of(D4, Black),
+            bit_board::with_bit_at(D8.into())
+        );
+    }
+
+    #[test]
+    fn attacks_by_standard_formation() {
+        const RANK_3: u64 = 0xff << 40;
+        const RANK_6: u64 = 0xff << 16;
+
+        let board = Board::new_with_standard_formation();
+        let white = board.attacks_by(White);
+        let black = board.attacks_by(Black);
+
+        assert_eq!(white & RANK_3, RANK_3);
+        assert_eq!(black & RANK_6, RANK_6);
+        // Nothing beyond the third (sixth) rank can be reached yet.
+        assert_eq!(white & !(RANK_3 | board.occupied_by(White)), 0);
+        assert_eq!(black & !(RANK_6 | board.occupied_by(Black)), 0);
+        // The rooks in the corners are the only pieces not defended.
+        assert!(!bit_board::is_bit_set(white, A1.into()));
+        assert!(!bit_board::is_bit_set(black, H8.into()));
+    }
+
+    #[test]
+    fn attacks_by_matches_is_pos_attacked_by() {
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+
+        for color in [Black, White] {
+            let attacks = board.attacks_by(color);
+
+            for i in 0..Board::SIZE {
+                assert_eq!(
+                    bit_board::is_bit_set(attacks, i),
+                    board.is_pos_attacked_by(i, &color),
+                    "{:?} {:?}",
+                    color,
+                    Square::try_from(i).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn color_at_and_is_square_empty_standard_formation() {
+        let board = Board::new_with_standard_formation();
+
+        assert!(board.is_square_empty(E4));
+        assert_eq!(board.color_at(E4), None);
+
+        for file in 0..Board::WIDTH {
+            assert!(!board.is_square_empty(file));
+            assert_eq!(board.color_at(file), Some(Black));
+            assert!(!board.is_square_empty(56 + file));
+            assert_eq!(board.color_at(56 + file), Some(White));
+        }
+    }
+
+    #[test]
+    fn default_is_starting_position() {
+        assert_eq!(
+            Board::default(),
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap()
+        );
+        assert_eq!(Board::starting_position(), Board::default());
+        assert_eq!(Board::default().zobrist(), Board::default().hash);
+    }
+
+    #[test]
+    fn flip_vertical() {
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/Pp2P3/2N2Q1p/1PPBBPPP/R3K2R b Kq a3 0 1")
+                .unwrap();
+        let flipped = board.flip_vertical();
+
+        assert_eq!(
+            flipped.get_fen(),
+            "r3k2r/1ppbbppp/2n2q1P/pP2p3/3pn3/BN2PNP1/P1PPQPB1/R3K2R w Qk a6 0 1"
+        );
+        assert_eq!(flipped.hash, flipped.zobrist());
+        assert_eq!(flipped.flip_vertical(), board);
+    }
+
+    #[test]
+    fn is_checkmate_fools_mate() {
+        let board =
+            Board::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+                .unwrap();
+
+        assert!(board.is_checkmate());
+        assert!(!board.is_stalemate());
+    }
+
+    #[test]
+    fn is_checkmate_standard_formation() {
+        let board = Board::new_with_standard_formation();
+
+        assert!(!board.is_checkmate());
+        assert!(!board.is_stalemate());
+    }
+
+    #[test]
+    fn is_checkmate_check_but_escapable() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 0").unwrap();
+        assert!(!board.is_checkmate());
+
+        let board = Board::from_fen("R3k3/8/8/8/8/8/8/4K3 b - - 0 0").unwrap();
+        assert!(!board.is_checkmate());
+    }
+
+    #[test]
+    fn is_checkmate_and_is_stalemate_empty_board() {
+        let board = Board::new_empty();
+
+        assert!(!board.is_checkmate());
+        assert!(!board.is_stalemate());
+    }
+
+    #[test]
+    fn is_stalemate_king_in_corner() {
+        let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 0").unwrap();
+
+        assert!(board.is_stalemate());
+        assert!(!board.is_checkmate());
+    }
+
+    #[test]
+    fn from_pieces_king_and_queen_vs_king() {
+        let pieces = [
+            (E8, PieceInstance::new(Black, King)),
+            (D1, PieceInstance::new(White, Queen)),
+            (E1, PieceInstance::new(White, King)),
+        ];
+        let expected = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+
+        assert_eq!(Board::from_pieces(pieces.clone()), expected);
+        assert_eq!(pieces.clone().into_iter().collect::<Board>(), expected);
+        assert_eq!(Board::from_pieces(pieces).hash, expected.hash);
+    }
+
+    #[test]
+    fn is_in_check_standard_formation() {
+        let board = Board::new_with_standard_formation();
+
+        assert!(!board.is_in_check());
+    }
+
+    #[test]
+    fn is_in_check_back_rank_rook() {
+        let board = Board::from_fen("3r2k1/8/8/8/8/8/5PPP/6K1 w - - 0 0").unwrap();
+        assert!(!board.is_in_check());
+
+        let board = Board::from_fen("6k1/8/8/8/8/8/5PPP/3r2K1 w - - 0 0").unwrap();
+        assert!(board.is_in_check());
+    }
+
+    #[test]
+    fn is_in_check_knight() {
+        let board = Board::from_fen("4k3/8/3N4/8/8/8/8/4K3 b - - 0 0").unwrap();
+
+        assert!(board.is_in_check());
+    }
+
+    #[test]
+    fn is_in_check_no_king() {
+        let mut board = Board::new_empty();
+        board.set(Black, Queen, E2);
+
+        assert!(!board.is_in_check());
+    }
+
+    #[test]
+    fn is_insufficient_material_lone_kings() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert!(board.is_insufficient_material());
+    }
+
+    #[test]
+    fn is_insufficient_material_single_minor_piece() {
+        for fen in [
+            "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/1N2K3 w - - 0 1",
+            "2b1k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            "1n2k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+
+            assert!(board.is_insufficient_material(), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn is_insufficient_material_same_color_bishops() {
+        // C1 and F8 are both dark squares.
+        let board = Board::from_fen("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1").unwrap();
+
+        assert!(board.is_insufficient_material());
+    }
+
+    #[test]
+    fn is_insufficient_material_opposite_color_bishops() {
+        // C1 is a dark, C8 a light square.
+        let board = Board::from_fen("2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1").unwrap();
+
+        assert!(!board.is_insufficient_material());
+    }
+
+    #[test]
+    fn is_insufficient_material_sufficient() {
+        for fen in [
+            "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+            "3qk3/8/8/8/8/8/8/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+
+            assert!(!board.is_insufficient_material(), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn is_pseudo_legal_genuine_and_stale_moves() {
+        let mut board = Board::new_with_standard_formation();
+        let knight_mv = Move::between(White, Knight, G1, F3);
+
+        assert!(board.is_pseudo_legal(&knight_mv));
+        assert!(board.is_pseudo_legal(&Move::new_dbl_push(White, E2, E4)));
+        assert!(!board.is_pseudo_legal(&Move::between(White, Knight, G1, E2)));
+        assert!(!board.is_pseudo_legal(&Move::between(White, Bishop, F1, C4)));
+        assert!(!board.is_pseudo_legal(&Move::new_castle(White, E1, G1)));
+
+        board.do_move(knight_mv.clone());
+        board.do_move(Move::between(Black, Knight, G8, F6));
+
+        // The knight is no longer on its source.
+        assert!(!board.is_pseudo_legal(&knight_mv));
+        // A piece of the wrong type on the source.
+        assert!(!board.is_pseudo_legal(&Move::between(White, Bishop, F3, E5)));
+        // Not the side to move.
+        assert!(!board.is_pseudo_legal(&Move::between(Black, Knight, F6, E4)));
+    }
+
+    #[test]
+    fn is_pseudo_legal_matches_all_moves() {
+        for seed in 0..200 {
+            let board = testing_utils::random_legal_position(seed);
+            let other = testing_utils::random_legal_position(seed + 1000);
+            let moves = move_generator::all_moves(&board);
+
+            for mv in &moves {
+                assert!(
+                    board.is_pseudo_legal(mv),
+                    "{} rejected in {}",
+                    mv,
+                    board.get_fen()
+                );
+            }
+
+            for mv in move_generator::all_moves(&other) {
+                assert_eq!(
+                    board.is_pseudo_legal(&mv),
+                    moves.contains(&mv),
+                    "{:?} in {}",
+                    mv,
+                    board.get_fen()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn is_threefold_repetition_knight_shuffle() {
+        let mut board = Board::new_with_standard_formation();
+
+        for i in 0..2 {
+            assert!(
+                !board.is_threefold_repetition(),
+                "repetition after {} cycles",
+                i
+            );
+
+            board.do_move(Move::new(White, Knight, G1, F3));
+            board.do_move(Move::new(Black, Knight, G8, F6));
+            board.do_move(Move::new(White, Knight, F3, G1));
+            board.do_move(Move::new(Black, Knight, F6, G8));
+        }
+
+        assert!(board.is_threefold_repetition());
+    }
+
+    #[test]
+    fn king_square() {
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(board.king_square(White), Some(E1));
+        assert_eq!(board.king_square(Black), Some(E8));
+
+        let board = Board::new_empty();
+
+        assert_eq!(board.king_square(White), None);
+        assert_eq!(board.king_square(Black), None);
+    }
+
+    #[test]
+    fn leaves_king_in_check() {
+        let board = Board::from_fen("8/8/8/8/8/8/1k6/R3K3 w - - 0 1").unwrap();
+
+        assert!(!board.leaves_king_in_check(White));
+        assert!(!board.leaves_king_in_check(Black));
+
+        let board = Board::from_fen("8/8/8/8/8/8/8/Rk2K3 w - - 0 1").unwrap();
+
+        assert!(board.leaves_king_in_check(Black));
+        assert!(!Board::new_empty().leaves_king_in_check(White));
+    }
+
+    #[test]
+    fn material_count_standard_formation() {
+        let count = Board::new_with_standard_formation().material_count();
+
+        for (_, count) in count.iter() {
+            assert_eq!(count[Bishop as usize], 2);
+            assert_eq!(count[King as usize], 1);
+            assert_eq!(count[Knight as usize], 2);
+            assert_eq!(count[Pawn as usize], 8);
+            assert_eq!(count[Queen as usize], 1);
+            assert_eq!(count[Rook as usize], 2);
+        }
+
+        assert_eq!(Board::new_empty().material_count(), ByColor::default());
+    }
+
+    #[test]
+    fn occupied_by_standard_formation() {
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(board.occupied_by(White), 0xffff_0000_0000_0000);
+        assert_eq!(board.occupied_by(Black), 0x0000_0000_0000_ffff);
+        assert_eq!(board.all_occupancies(), 0xffff_0000_0000_ffff);
+    }
+
+    #[test]
+    fn piece_bitboard_standard_formation() {
+        const RANK_2: u64 = 0xff << 48;
+
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(board.piece_bitboard(White, Pawn), RANK_2);
+        assert_eq!(
+            board.piece_bitboard(Black, King),
+            bit_board::with_bit_at(E8.into())
+        );
+        assert_eq!(
+            Piece::all()
+                .into_iter()
+                .map(|piece| board.piece_bitboard(White, piece))
+                .fold(0, |acc, bit_board| acc | bit_board),
+            board.occupied_by(White)
+        );
+    }
+
+    #[test]
+    fn pieces_standard_formation() {
+        let board = Board::new_with_standard_formation();
+        let pieces = board.pieces().collect::<Vec<_>>();
+
+        assert_eq!(pieces.len(), 32);
+        assert!(pieces.contains(&(E1, PieceInstance::new(White, King))));
+        assert!(pieces.contains(&(D8, PieceInstance::new(Black, Queen))));
+        assert!(pieces
+            .iter()
+            .all(|(sq, ins)| board.get(*sq).as_ref() == Some(ins)));
+    }
+
+    #[test]
+    fn pinned_pieces_knight_pinned_by_rook() {
+        let board = Board::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.pinned_pieces(White),
+            bit_board::with_bit_at(E2.into())
+        );
+        assert_eq!(board.pinned_pieces(Black), 0);
+    }
+
+    #[test]
+    fn pinned_pieces_pawn_pinned_by_bishop() {
+        let board = Board::from_fen("6k1/8/8/1b6/8/3P4/4K3/8 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.pinned_pieces(White),
+            bit_board::with_bit_at(D3.into())
+        );
+    }
+
+    #[test]
+    fn pinned_pieces_not_pinned() {
+        // Two pieces between the king and the rook, neither of them is pinned.
+        let board = Board::from_fen("4r1k1/8/8/8/4P3/8/4N3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.pinned_pieces(White), 0);
+
+        // An opposing piece in between blocks the ray.
+        let board = Board::from_fen("4r1k1/8/8/8/4p3/8/4N3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.pinned_pieces(White), 0);
+
+        // Not on the same line as the king.
+        let board = Board::from_fen("3r2k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.pinned_pieces(White), 0);
+    }
+
+    #[test]
+    fn pinned_pieces_multiple_and_queen() {
+        let board = Board::from_fen("4r1k1/8/8/q7/8/8/3PN3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.pinned_pieces(White),
+            bit_board::with_bit_at(D2.into()) | bit_board::with_bit_at(E2.into())
+        );
+    }
+
+    #[test]
+    fn position_key_same_position() {
+        let board = Board::new_with_standard_formation();
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+        assert_eq!(
+            board.position_key(),
+            Board::from_fen(fen).unwrap().position_key()
+        );
+    }
+
+    #[test]
+    fn position_key_distinguishes_side_to_move_and_castling_rights() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        for other_fen in [
+            "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w Qkq - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w KQkq e3 0 1",
+        ] {
+            let other = Board::from_fen(other_fen).unwrap();
+
+            assert_ne!(board.position_key(), other.position_key(), "{}", other_fen);
+        }
+    }
+
+    #[test]
+    fn result_ongoing() {
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(board.result(), GameResult::Ongoing);
+    }
+
+    #[test]
+    fn result_checkmate() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2")
+                .unwrap();
+        assert_eq!(board.result(), GameResult::Ongoing);
+
+        board.do_move(Move::new(Black, Queen, D8, H4));
+        assert_eq!(board.result(), GameResult::BlackWins);
+
+        let mut board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        board.do_move(Move::new(White, Rook, A1, A8));
+        assert_eq!(board.result(), GameResult::WhiteWins);
+    }
+
+    #[test]
+    fn result_stalemate() {
+        let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+
+        assert_eq!(board.result(), GameResult::Draw);
+    }
+
+    #[test]
+    fn result_insufficient_material() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/1N2K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.result(), GameResult::Draw);
+    }
+
+    #[test]
+    fn result_fifty_and_seventy_five_move_rule() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+
+        board.half_move_clock = 99;
+        assert_eq!(board.result(), GameResult::Ongoing);
+        assert!(!board.can_claim_fifty_move_draw());
+
+        board.half_move_clock = 100;
+        assert_eq!(board.result(), GameResult::Ongoing);
+        assert!(board.can_claim_fifty_move_draw());
+
+        board.half_move_clock = 150;
+        assert_eq!(board.result(), GameResult::Draw);
+        assert!(board.can_claim_fifty_move_draw());
+    }
+
+    #[test]
+    fn side_to_move() {
+        let mut board = Board::new_with_standard_formation();
+
+        assert_eq!(board.side_to_move(), White);
+
+        board.set_side_to_move(Black);
+
+        assert_eq!(board.side_to_move(), Black);
+        assert_eq!(
+            board.get_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1"
+        );
+        assert_eq!(board.hash, board.zobrist());
+
+        board.set_side_to_move(Black);
+
+        assert_eq!(board.hash, board.zobrist());
+    }
+
+    #[test]
+    fn en_passant_square() {
+        let mut board = Board::new_with_standard_formation();
+
+        assert_eq!(board.en_passant_square(), None);
+
+        board.set_en_passant_square(Some(E3));
+
+        assert_eq!(board.en_passant_square(), Some(E3));
+        assert_eq!(board.en_passant_target_idx, Some(usize::from(E3)));
+        assert_eq!(board.hash, board.zobrist());
+
+        board.set_en_passant_square(None);
+
+        assert_eq!(board.en_passant_square(), None);
+        assert_eq!(board.hash, board.zobrist());
+    }
+
+    #[test]
+    fn set_piece_fen_round_trip() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let board = Board::from_fen(fen).unwrap();
+
+        assert_eq!(board.get_fen(), fen);
+        assert_eq!(board.get(A6), Some(PieceInstance::new(Black, Bishop)));
+        assert_eq!(board.get(F3), Some(PieceInstance::new(White, Queen)));
+
+        let mut set_board = Board::new_empty();
+
+        for (sq, ins) in board.pieces() {
+            set_board.set_piece(sq, ins);
+        }
+
+        assert_eq!(set_board.get(A6), board.get(A6));
+        assert_eq!(set_board.zobrist(), set_board.hash);
+        assert_eq!(
+            set_board.pieces().collect::<Vec<_>>(),
+            board.pieces().collect::<Vec<_>>()
+        );
     }
 
-    pub fn is_pos_attacked_by(&self, pos: impl BoardPos, atk_color: &Color) -> bool {
-        // Since the attacks are essentially mirrored for both sides, we just generate
-        // the opponent attacks on the square to check. If the attack includes the
-        // position if our piece, we can be attacked, and the reverse is also true.
-        //
-        // Let's say we want to see if a white pawn on E5 can attack the square D6:
-        //
-        // 8   . . . . . . . .
-        // 7   . . . . . . . .
-        // 6   . . . . . . . .
-        // 5   . . . . 1 . . .
-        // 4   . . . . . . . .
-        // 3   . . . . . . . .
-        // 2   . . . . . . . .
-        // 1   . . . . . . . .
-        //
-        //     a b c d e f g h
-        //
-        // We now simply lookup the attacks of the **opponent** on the position we
-        // want to check (pawn attacks of the square D6):
-        //
-        // 8   . . . . . . . .
-        // 7   . . . . . . . .
-        // 6   . . . . . . . .
-        // 5   . . 1 . 1 . . .
-        // 4   . . . . . . . .
-        // 3   . . . . . . . .
-        // 2   . . . . . . . .
-        // 1   . . . . . . . .
-        //
-        //     a b c d e f g h
-        //
-        // We can see that the bit on E5 is set on both boards, thus the square
-        // D6 can be attacked by the white pawn on E5.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let json = serde_json::to_string(&board).unwrap();
 
-        let all_occ = self.all_occupancies();
-        let def_color = atk_color.opposing();
+        assert_eq!(json, format!("\"{}\"", board.get_fen()));
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+        assert!(serde_json::from_str::<Board>("\"not a fen\"").is_err());
 
-        if bit_board::has_set_bits(
-            piece::get_bishop_attacks_for(pos, all_occ) & self.bishops[*atk_color],
-        ) {
-            return true;
-        }
+        let ins = PieceInstance::new(Black, Knight);
+        let json = serde_json::to_string(&ins).unwrap();
 
-        if bit_board::has_set_bits(piece::get_king_attack_mask_for(pos) & self.king[*atk_color]) {
-            return true;
-        }
+        assert_eq!(serde_json::from_str::<PieceInstance>(&json).unwrap(), ins);
+    }
 
-        if bit_board::has_set_bits(
-            piece::get_knight_attack_mask_for(pos) & self.knights[*atk_color],
-        ) {
-            return true;
-        }
+    #[test]
+    fn display_has_no_escape_sequences() {
+        let board = Board::new_with_standard_formation();
 
-        if bit_board::has_set_bits(
-            piece::get_pawn_attacks_for(pos, &def_color) & self.pawns[*atk_color],
-        ) {
-            return true;
-        }
+        assert!(!format!("{}", board).contains('\u{001b}'));
+        assert!(board.to_ansi().contains('\u{001b}'));
+    }
 
-        if bit_board::has_set_bits(
-            piece::get_queen_attacks_for(pos, all_occ) & self.queens[*atk_color],
-        ) {
-            return true;
-        }
+    #[test]
+    fn see_undefended_piece() {
+        let board = Board::from_fen("4k3/8/8/3r4/8/8/8/3RK3 w - - 0 1").unwrap();
 
-        if bit_board::has_set_bits(
-            piece::get_rook_attacks_for(pos, all_occ) & self.rooks[*atk_color],
-        ) {
-            return true;
-        }
+        assert_eq!(board.see(&Move::new(White, Rook, D1, D5)), 5);
+    }
 
-        false
+    #[test]
+    fn see_defended_pawn() {
+        let board = Board::from_fen("4k3/4p3/3p4/8/8/8/8/3QK3 w - - 0 1").unwrap();
+
+        assert_eq!(board.see(&Move::new(White, Queen, D1, D6)), 1 - 9);
     }
 
-    pub fn new_empty() -> Self {
-        Self {
-            bishops: [0; 2],
-            can_black_castle_king_side: false,
-            can_black_castle_queen_side: false,
-            can_white_castle_king_side: false,
-            can_white_castle_queen_side: false,
-            en_passant_target_idx: None,
-            is_whites_turn: true,
-            king: [0; 2],
-            knights: [0; 2],
-            pawns: [0; 2],
-            promote_idx: None,
-            queens: [0; 2],
-            rooks: [0; 2],
-        }
+    #[test]
+    fn see_equal_trade() {
+        let board = Board::from_fen("4k3/4p3/3n4/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.see(&Move::new(White, Knight, E4, D6)), 0);
     }
 
-    pub fn new_with_standard_formation() -> Self {
-        let mut board = Self::new_empty();
+    #[test]
+    fn see_x_ray_and_stopping() {
+        // The second rook behind the first wins the exchange on D5.
+        let board = Board::from_fen("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1").unwrap();
 
-        board.can_black_castle_king_side = true;
-        board.can_black_castle_queen_side = true;
-        board.can_white_castle_king_side = true;
-        board.can_white_castle_queen_side = true;
-
-        // Standard chess formation:
-        board.set(Color::Black, Piece::Rook, 0);
-        board.set(Color::Black, Piece::Knight, 1);
-        board.set(Color::Black, Piece::Bishop, 2);
-        board.set(Color::Black, Piece::Queen, 3);
-        board.set(Color::Black, Piece::King, 4);
-        board.set(Color::Black, Piece::Bishop, 5);
-        board.set(Color::Black, Piece::Knight, 6);
-        board.set(Color::Black, Piece::Rook, 7);
-
-        board.set(Color::Black, Piece::Pawn, 8);
-        board.set(Color::Black, Piece::Pawn, 9);
-        board.set(Color::Black, Piece::Pawn, 10);
-        board.set(Color::Black, Piece::Pawn, 11);
-        board.set(Color::Black, Piece::Pawn, 12);
-        board.set(Color::Black, Piece::Pawn, 13);
-        board.set(Color::Black, Piece::Pawn, 14);
-        board.set(Color::Black, Piece::Pawn, 15);
-
-        board.set(Color::White, Piece::Pawn, 48);
-        board.set(Color::White, Piece::Pawn, 49);
-        board.set(Color::White, Piece::Pawn, 50);
-        board.set(Color::White, Piece::Pawn, 51);
-        board.set(Color::White, Piece::Pawn, 52);
-        board.set(Color::White, Piece::Pawn, 53);
-        board.set(Color::White, Piece::Pawn, 54);
-        board.set(Color::White, Piece::Pawn, 55);
-
-        board.set(Color::White, Piece::Rook, 56);
-        board.set(Color::White, Piece::Knight, 57);
-        board.set(Color::White, Piece::Bishop, 58);
-        board.set(Color::White, Piece::Queen, 59);
-        board.set(Color::White, Piece::King, 60);
-        board.set(Color::White, Piece::Bishop, 61);
-        board.set(Color::White, Piece::Knight, 62);
-        board.set(Color::White, Piece::Rook, 63);
+        assert_eq!(board.see(&Move::new(White, Rook, D2, D5)), 1);
 
-        board
+        // Black doesn't recapture with the queen, as the bishop defends the rook.
+        let board = Board::from_fen("3qk3/8/8/3p4/8/1B6/8/3RK3 w - - 0 1").unwrap();
+
+        assert_eq!(board.see(&Move::new(White, Rook, D1, D5)), 1);
+
+        // The king can only capture undefended pieces.
+        let board = Board::from_fen("8/8/8/3p4/4k3/8/8/3RK3 w - - 0 1").unwrap();
+
+        assert_eq!(board.see(&Move::new(White, Rook, D1, D5)), 1 - 5);
+
+        let board = Board::from_fen("B7/8/8/3p4/4k3/8/8/3QK3 w - - 0 1").unwrap();
+
+        assert_eq!(board.see(&Move::new(White, Queen, D1, D5)), 1);
     }
 
-    /// Get the occupied squares of a certain color.
-    // TODO: This function exposes information (bitboard) that should (?) be abstracted
-    // away.
-    pub fn occupancies_of(&self, color: Color) -> u64 {
-        self.bishops[color]
-            | self.king[color]
-            | self.knights[color]
-            | self.pawns[color]
-            | self.queens[color]
-            | self.rooks[color]
+    #[test]
+    fn to_ascii_standard_formation() {
+        assert_eq!(
+            Board::new_with_standard_formation().to_ascii(),
+            [
+                "8   r n b q k b n r",
+                "7   p p p p p p p p",
+                "6   . . . . . . . .",
+                "5   . . . . . . . .",
+                "4   . . . . . . . .",
+                "3   . . . . . . . .",
+                "2   P P P P P P P P",
+                "1   R N B Q K B N R",
+                "",
+                "    a b c d e f g h",
+            ]
+            .join("\n")
+        );
     }
 
-    /// Set (add) a piece on the specified location
-    // TODO: convert parameters to references
-    pub fn set(&mut self, color: Color, piece: Piece, pos: impl BoardPos) {
-        let i = pos.into();
+    #[test]
+    fn hash_equal_boards() {
+        let mut boards = HashSet::new();
 
-        match piece {
-            Piece::Bishop => bit_board::set_bit(&mut self.bishops[color], i),
-            Piece::King => bit_board::set_bit(&mut self.king[color], i),
-            Piece::Knight => bit_board::set_bit(&mut self.knights[color], i),
-            Piece::Pawn => bit_board::set_bit(&mut self.pawns[color], i),
-            Piece::Queen => bit_board::set_bit(&mut self.queens[color], i),
-            Piece::Rook => bit_board::set_bit(&mut self.rooks[color], i),
-        }
+        boards.insert(Board::new_with_standard_formation());
+        boards.insert(Board::from_fen(fen::STARTING_FEN).unwrap());
+
+        assert_eq!(boards.len(), 1);
+
+        let mut board = Board::new_with_standard_formation();
+        board.do_move(Move::new_dbl_push(White, E2, E4));
+        boards.insert(board);
+
+        assert_eq!(boards.len(), 2);
     }
-}
 
-impl Display for Board {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut val = String::new();
+    #[test]
+    fn eq_ignores_history() {
+        let mut board = Board::new_with_standard_formation();
+        board.do_move(Move::new_dbl_push(White, E2, E4));
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
 
-        for i in 0..Board::SIZE {
-            let file = i % Board::HEIGHT;
-            let rank = i / Board::HEIGHT;
+        assert_eq!(board, Board::from_fen(fen).unwrap());
+    }
 
-            if file == 0 {
-                val += &format!("{}  ", Board::HEIGHT - rank);
-            }
+    #[test]
+    fn zobrist_same_position() {
+        let board = Board::new_with_standard_formation();
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
-            let sym = match self.get(i) {
-                Some(ins) => ins.piece.symbol(ins.color).to_owned(),
-                None => ".".to_owned(),
-            };
+        assert_eq!(board.zobrist(), Board::from_fen(fen).unwrap().zobrist());
+    }
 
-            val += &format!(" {}", sym);
+    #[test]
+    fn zobrist_distinguishes_side_to_move_and_castling_rights() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        for other_fen in [
+            "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w Qkq - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w KQkq e3 0 1",
+        ] {
+            let other = Board::from_fen(other_fen).unwrap();
 
-            if file == 7 {
-                val += "\n";
-            }
+            assert_ne!(board.zobrist(), other.zobrist(), "{}", other_fen);
         }
+    }
 
-        val += "\n    a b c d e f g h";
+    #[test]
+    fn zobrist_distinguishes_piece_placement() {
+        let board = Board::new_with_standard_formation();
+        let other =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1").unwrap();
 
-        val += "\n    side to move: ";
-        val += if self.is_whites_turn {
-            "white"
-        } else {
-            "black"
+        assert_ne!(board.zobrist(), other.zobrist());
+    }
+
+    #[test]
+    fn zobrist_incremental_random_game() {
+        // Xorshift with a fixed seed, so the test is deterministic.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut random = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize
         };
 
-        val += "\n    en passant target: ";
-        val += &self
-            .en_passant_target_idx
-            .map(|i| format!("{:?}", Square::try_from(i).unwrap()))
-            .unwrap_or_else(|| "<None>".to_owned());
+        let mut board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
 
-        write!(f, "{}", val)
-    }
-}
+        for _ in 0..3000 {
+            let moves = move_generator::legal_moves(&board);
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct PieceInstance {
-    pub color: Color,
-    pub piece: Piece,
-}
+            if moves.is_empty() || board.half_move_clock >= 100 {
+                board = Board::new_with_standard_formation();
+                continue;
+            }
 
-impl PieceInstance {
-    pub fn new(color: Color, piece: Piece) -> Self {
-        Self { piece, color }
+            let mv = moves[random() % moves.len()].clone();
+
+            // Occasionally take the move back, to verify the restoration as well.
+            if random() % 4 == 0 {
+                let undo = board.make_move(mv.clone());
+                assert_eq!(board.hash, board.zobrist(), "after {}", mv);
+                board.unmake_move(mv.clone(), undo);
+            }
+
+            board.make_move(mv.clone());
+            assert_eq!(board.hash, board.zobrist(), "after {}", mv);
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{
-        bit_board::{NORTH, SOUTH},
-        fen::Fen,
-    };
+    #[test]
+    fn zobrist_transposition() {
+        let mut board = Board::new_with_standard_formation();
+        for mv in [
+            Move::new(White, Knight, G1, F3),
+            Move::new(Black, Knight, G8, F6),
+            Move::new(White, Knight, B1, C3),
+        ] {
+            board.do_move(mv);
+        }
 
-    use super::*;
+        let mut other = Board::new_with_standard_formation();
+        for mv in [
+            Move::new(White, Knight, B1, C3),
+            Move::new(Black, Knight, G8, F6),
+            Move::new(White, Knight, G1, F3),
+        ] {
+            other.do_move(mv);
+        }
 
-    use Square::*;
+        assert_eq!(board.zobrist(), other.zobrist());
+    }
 
     #[test]
     fn is_pos_attacked_not_attacked() {
         let board = Board::new_empty();
 
-        assert_eq!(board.is_pos_attacked_by(A8, &Color::Black), false);
-        assert_eq!(board.is_pos_attacked_by(A8, &Color::White), false);
+        assert!(!board.is_pos_attacked_by(A8, &Color::Black));
+        assert!(!board.is_pos_attacked_by(A8, &Color::White));
     }
 
     #[test]
     fn is_pos_attacked_by_bishop_no_blockers() {
         for color in [Color::Black, Color::White] {
             let mut board = Board::new_empty();
-            board.set(color.clone(), Piece::Bishop, F4);
+            board.set(color, Piece::Bishop, F4);
 
             for pos in [B8, C7, D6, E5, H6, G5, E3, D2, C1, G3, H2] {
-                assert_eq!(board.is_pos_attacked_by(pos, &color), true, "{:?}", &color);
+                assert!(board.is_pos_attacked_by(pos, &color), "{:?}", &color);
             }
         }
     }
@@ -500,7 +2185,7 @@ mod tests {
 
         for atk_color in &[Color::Black, Color::White] {
             let mut board = Board::new_empty();
-            board.set(atk_color.clone(), Piece::Bishop, B3);
+            board.set(*atk_color, Piece::Bishop, B3);
 
             let var_name: [(Color, Piece, &[Square]); 12] = [
                 // Opposing blocking pieces
@@ -524,9 +2209,8 @@ mod tests {
                 board.set(blocking_color, blocking_piece, D5);
 
                 for pos in blocked_squares {
-                    assert_eq!(
-                        board.is_pos_attacked_by(*pos, &atk_color),
-                        false,
+                    assert!(
+                        !board.is_pos_attacked_by(*pos, atk_color),
                         "attacking: {:?}, blocking: {:?} {:?}",
                         atk_color,
                         atk_color,
@@ -541,10 +2225,10 @@ mod tests {
     fn is_pos_attacked_by_king() {
         for color in [Color::Black, Color::White] {
             let mut board = Board::new_empty();
-            board.set(color.clone(), Piece::King, F7);
+            board.set(color, Piece::King, F7);
 
             for pos in [E8, F8, G8, E7, G7, E6, F6, G6] {
-                assert_eq!(board.is_pos_attacked_by(pos, &color), true, "{:?}", &color);
+                assert!(board.is_pos_attacked_by(pos, &color), "{:?}", &color);
             }
         }
     }
@@ -553,10 +2237,10 @@ mod tests {
     fn is_pos_attacked_by_knight() {
         for color in [Color::Black, Color::White] {
             let mut board = Board::new_empty();
-            board.set(color.clone(), Piece::Knight, B4);
+            board.set(color, Piece::Knight, B4);
 
             for pos in [A6, C6, D5, D3, C2, A2] {
-                assert_eq!(board.is_pos_attacked_by(pos, &color), true, "{:?}", &color);
+                assert!(board.is_pos_attacked_by(pos, &color), "{:?}", &color);
             }
         }
     }
@@ -613,10 +2297,10 @@ mod tests {
     fn is_pos_attacked_by_rook_no_blockers() {
         for color in [Color::Black, Color::White] {
             let mut board = Board::new_empty();
-            board.set(color.clone(), Piece::Rook, G7);
+            board.set(color, Piece::Rook, G7);
 
             for pos in [A7, B7, C7, D7, E7, F7, H7, G8, G6, G5, G4, G3, G2, G1] {
-                assert_eq!(board.is_pos_attacked_by(pos, &color), true, "{:?}", &color);
+                assert!(board.is_pos_attacked_by(pos, &color), "{:?}", &color);
             }
         }
     }
@@ -627,7 +2311,7 @@ mod tests {
 
         for atk_color in &[Color::Black, Color::White] {
             let mut board = Board::new_empty();
-            board.set(atk_color.clone(), Piece::Rook, D2);
+            board.set(*atk_color, Piece::Rook, D2);
 
             let var_name: [(Color, Piece, &[Square]); 12] = [
                 // Opposing blocking pieces
@@ -651,9 +2335,8 @@ mod tests {
                 board.set(blocking_color, blocking_piece, D4);
 
                 for pos in blocked_squares {
-                    assert_eq!(
-                        board.is_pos_attacked_by(*pos, &atk_color),
-                        false,
+                    assert!(
+                        !board.is_pos_attacked_by(*pos, atk_color),
                         "attacking: {:?}, blocking: {:?} {:?}",
                         atk_color,
                         atk_color,
@@ -666,44 +2349,86 @@ mod tests {
 
     #[test]
     fn do_move() {
-        let mut board = Board::from_fen("1n2k3/7p/8/8/8/8/P7/4K3 w - - 0 0").unwrap();
+        let mut board = Board::from_fen("1n2k3/7p/8/8/8/8/P7/4K3 w - - 0 1").unwrap();
 
         board.do_move(Move::new(White, Pawn, A2, A3));
-        assert_eq!(board.get_fen(), "1n2k3/7p/8/8/8/P7/8/4K3 b - - 0 0");
+        assert_eq!(board.get_fen(), "1n2k3/7p/8/8/8/P7/8/4K3 b - - 0 1");
 
         board.do_move(Move::new(Black, Pawn, H7, H6));
-        assert_eq!(board.get_fen(), "1n2k3/8/7p/8/8/P7/8/4K3 w - - 0 0");
+        assert_eq!(board.get_fen(), "1n2k3/8/7p/8/8/P7/8/4K3 w - - 0 2");
 
         board.do_move(Move::new(Black, Knight, B8, A6));
-        assert_eq!(board.get_fen(), "4k3/8/n6p/8/8/P7/8/4K3 b - - 0 0");
+        assert_eq!(board.get_fen(), "4k3/8/n6p/8/8/P7/8/4K3 b - - 1 3");
+    }
+
+    #[test]
+    fn do_move_returns_true_for_legal_move() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/4B3/4K3 w - - 0 1").unwrap();
+
+        assert!(board.do_move(Move::new(White, Bishop, E2, D3)));
+        assert_eq!(board.get_fen(), "4k3/8/8/8/8/3B4/8/4K3 b - - 1 1");
+    }
+
+    #[test]
+    fn do_move_rolls_back_move_leaving_king_in_check() {
+        let mut board = Board::from_fen("4r3/8/8/8/8/8/4B3/4K3 w - - 0 0").unwrap();
+        let board_bak = board.clone();
+
+        assert!(!board.do_move(Move::new(White, Bishop, E2, D3)));
+        assert_eq!(board, board_bak);
+    }
+
+    #[test]
+    fn try_do_move_legal() {
+        let mut board = Board::from_fen("r3k3/8/8/8/8/8/8/R3K3 w Qq - 0 1").unwrap();
+
+        // The castle is recognized, even if the move isn't flagged as such.
+        assert!(board.try_do_move(Move::new(White, King, E1, C1)).is_ok());
+        assert_eq!(board.get_fen(), "r3k3/8/8/8/8/8/8/2KR4 b q - 1 1");
+    }
+
+    #[test]
+    fn try_do_move_illegal() {
+        let mut board = Board::from_fen("4r3/8/8/8/8/8/4B3/4K3 w - - 0 1").unwrap();
+        let board_bak = board.clone();
+
+        // Leaves the king in check.
+        assert!(board.try_do_move(Move::new(White, Bishop, E2, D3)).is_err());
+        // Not a move of that piece.
+        assert!(board.try_do_move(Move::new(White, Bishop, E2, E4)).is_err());
+        // Empty square.
+        assert!(board.try_do_move(Move::new(White, Rook, A1, A2)).is_err());
+        // Not the side to move.
+        assert!(board.try_do_move(Move::new(Black, Rook, E8, D8)).is_err());
+        assert_eq!(board, board_bak);
     }
 
     #[test]
     fn do_move_castling_rights_removed_rook_moved() {
-        let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 0").unwrap();
+        let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
 
         board.do_move(Move::new(White, Rook, H1, H2));
-        assert_eq!(board.get_fen(), "r3k2r/8/8/8/8/8/7R/R3K3 b Qkq - 0 0");
+        assert_eq!(board.get_fen(), "r3k2r/8/8/8/8/8/7R/R3K3 b Qkq - 1 1");
 
         board.do_move(Move::new(White, Rook, A1, A2));
-        assert_eq!(board.get_fen(), "r3k2r/8/8/8/8/8/R6R/4K3 w kq - 0 0");
+        assert_eq!(board.get_fen(), "r3k2r/8/8/8/8/8/R6R/4K3 w kq - 2 1");
 
         board.do_move(Move::new(Black, Rook, H8, H7));
-        assert_eq!(board.get_fen(), "r3k3/7r/8/8/8/8/R6R/4K3 b q - 0 0");
+        assert_eq!(board.get_fen(), "r3k3/7r/8/8/8/8/R6R/4K3 b q - 3 2");
 
         board.do_move(Move::new(Black, Rook, A8, A7));
-        assert_eq!(board.get_fen(), "4k3/r6r/8/8/8/8/R6R/4K3 w - - 0 0");
+        assert_eq!(board.get_fen(), "4k3/r6r/8/8/8/8/R6R/4K3 w - - 4 3");
     }
 
     #[test]
     fn do_move_castling_rights_removed_king_moved() {
-        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w KQkq - 0 0").unwrap();
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w KQkq - 0 1").unwrap();
         board.do_move(Move::new(White, King, E1, E2));
-        assert_eq!(board.get_fen(), "4k3/8/8/8/8/8/4K3/8 b kq - 0 0");
+        assert_eq!(board.get_fen(), "4k3/8/8/8/8/8/4K3/8 b kq - 1 1");
 
-        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w KQkq - 0 0").unwrap();
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w KQkq - 0 1").unwrap();
         board.do_move(Move::new(Black, King, E8, E7));
-        assert_eq!(board.get_fen(), "8/4k3/8/8/8/8/8/4K3 b KQ - 0 0");
+        assert_eq!(board.get_fen(), "8/4k3/8/8/8/8/8/4K3 b KQ - 1 2");
     }
 
     #[test]
@@ -711,28 +2436,27 @@ mod tests {
         let mut board = Board::from_fen("r3k2r/p7/8/8/8/8/P7/R3K2R w KQkq - 0 0").unwrap();
 
         board.do_move(Move::new(White, Pawn, A2, A3));
-        assert!(board.can_white_castle_king_side);
-        assert!(board.can_white_castle_queen_side);
+        assert!(board.castling_rights.has(White, KingSide));
+        assert!(board.castling_rights.has(White, QueenSide));
 
         board.do_move(Move::new(Black, Pawn, A7, A6));
-        assert!(board.can_black_castle_king_side);
-        assert!(board.can_black_castle_queen_side);
+        assert!(board.castling_rights.has(Black, KingSide));
+        assert!(board.castling_rights.has(Black, QueenSide));
     }
 
     #[test]
     fn do_move_capture() {
-        let mut board = Board::from_fen("4k3/8/2n5/r7/8/8/3B4/4K3 w - - 0 0").unwrap();
+        let mut board = Board::from_fen("4k3/8/2n5/r7/8/8/3B4/4K3 w - - 0 1").unwrap();
 
         board.do_move(Move::new(White, Bishop, D2, A5));
-        assert_eq!(board.get_fen(), "4k3/8/2n5/B7/8/8/8/4K3 b - - 0 0");
+        assert_eq!(board.get_fen(), "4k3/8/2n5/B7/8/8/8/4K3 b - - 0 1");
 
         board.do_move(Move::new(Black, Knight, C6, A5));
-        assert_eq!(board.get_fen(), "4k3/8/8/n7/8/8/8/4K3 w - - 0 0");
+        assert_eq!(board.get_fen(), "4k3/8/8/n7/8/8/8/4K3 w - - 0 2");
         // TODO: Investigate if this "low" level bitboard access is necessary.
         // It breaks the abstraction provided by the board.
-        assert_eq!(
-            bit_board::is_bit_set(board.bishops[White], A5.into()),
-            false,
+        assert!(
+            !bit_board::is_bit_set(board.bishops[White], A5.into()),
             "bishop was not cleared"
         );
     }
@@ -743,10 +2467,18 @@ mod tests {
         let mut board_black_queen = Board::new_empty();
         let mut board_white_king = Board::new_empty();
         let mut board_white_queen = Board::new_empty();
-        board_black_king.can_black_castle_king_side = true;
-        board_black_queen.can_black_castle_queen_side = true;
-        board_white_king.can_black_castle_king_side = true;
-        board_white_queen.can_white_castle_queen_side = true;
+        let mut rights = CastlingRights::NONE;
+        rights.add(Black, KingSide);
+        board_black_king.set_castling_rights(rights);
+        let mut rights = CastlingRights::NONE;
+        rights.add(Black, QueenSide);
+        board_black_queen.set_castling_rights(rights);
+        let mut rights = CastlingRights::NONE;
+        rights.add(Black, KingSide);
+        board_white_king.set_castling_rights(rights);
+        let mut rights = CastlingRights::NONE;
+        rights.add(White, QueenSide);
+        board_white_queen.set_castling_rights(rights);
 
         for (mut board, color, king_src, king_dst, rook_src, rook_dst) in [
             (board_white_king, White, E1, G1, H1, F1),
@@ -805,7 +2537,7 @@ mod tests {
             board.set(White, King, E1);
             board.set(color, Pawn, src);
             board.set(color.opposing(), Pawn, en_pass_cap_idx);
-            board.en_passant_target_idx = Some(dst.into());
+            board.set_en_passant_square(Some(dst));
 
             board.do_move(Move::new_en_pass(color, src, dst));
             assert_eq!(board.get(en_pass_cap_idx), None);
@@ -822,6 +2554,25 @@ mod tests {
         assert_eq!(board.en_passant_target_idx, None);
     }
 
+    #[test]
+    fn do_move_en_passant_clear_flag_after_knight_move() {
+        let mut board = Board::from_fen("1n2k3/8/8/8/1p6/8/P7/4K1N1 w - - 0 0").unwrap();
+
+        board.do_move(Move::new_dbl_push(White, A2, A4));
+        assert_eq!(board.en_passant_target_idx, Some(A3.into()));
+        assert!(move_generator::all_moves(&board)
+            .iter()
+            .any(|mv| mv.is_en_passant()));
+
+        board.do_move(Move::new(Black, Knight, B8, C6));
+        assert_eq!(board.en_passant_target_idx, None);
+
+        board.do_move(Move::new(White, Knight, G1, F3));
+        assert!(!move_generator::all_moves(&board)
+            .iter()
+            .any(|mv| mv.is_en_passant()));
+    }
+
     #[test]
     fn do_move_pawn_promotion() {
         for (color, prom_to, src, dst) in [
@@ -834,23 +2585,104 @@ mod tests {
 
             board.do_move(Move::new_prom(color, src, dst, prom_to));
             assert_eq!(board.get(dst), Some(PieceInstance::new(color, prom_to)));
-            assert_eq!(
-                bit_board::is_bit_set(board.pawns[color], dst.into()),
-                false,
+            assert!(
+                !bit_board::is_bit_set(board.pawns[color], dst.into()),
                 "promoted pawn was not cleared",
             );
         }
     }
 
+    #[test]
+    fn do_move_half_move_clock() {
+        let mut board = Board::from_fen("4k3/p7/8/1n6/8/8/8/R3K3 w - - 5 0").unwrap();
+
+        board.do_move(Move::new(White, Rook, A1, A2));
+        assert_eq!(board.half_move_clock, 6);
+
+        board.do_move(Move::new_dbl_push(Black, A7, A5));
+        assert_eq!(board.half_move_clock, 0);
+
+        board.do_move(Move::new(White, King, E1, D1));
+        assert_eq!(board.half_move_clock, 1);
+
+        board.do_move(Move::new(Black, Knight, B5, A3));
+        assert_eq!(board.half_move_clock, 2);
+
+        board.do_move(Move::new(White, Rook, A2, A3));
+        assert_eq!(board.half_move_clock, 0);
+    }
+
+    #[test]
+    fn do_move_full_move_number() {
+        let mut board = Board::new_with_standard_formation();
+
+        board.do_move(Move::new_dbl_push(White, E2, E4));
+        assert_eq!(board.full_move_number, 1);
+
+        board.do_move(Move::new_dbl_push(Black, E7, E5));
+        assert_eq!(board.full_move_number, 2);
+    }
+
+    #[test]
+    fn make_null_move_unmake_null_move_restores_board() {
+        let mut board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+        let board_bak = board.clone();
+
+        let prev_ep = board.make_null_move();
+
+        assert_eq!(prev_ep, Some(D6.into()));
+        assert_eq!(board.get_fen(), "4k3/8/8/3pP3/8/8/8/4K3 b - - 0 2");
+        assert_eq!(board.hash, board.zobrist());
+
+        board.unmake_null_move(prev_ep);
+
+        assert_eq!(board, board_bak);
+        assert_eq!(board.hash, board_bak.hash);
+    }
+
+    #[test]
+    fn make_move_unmake_move_restores_board() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "4k3/8/8/3pP3/8/8/8/4K3 w - d6 4 20",
+            "r3k2r/1P4P1/8/8/8/8/1p4p1/R3K2R b KQkq - 0 1",
+        ] {
+            let mut board = Board::from_fen(fen).unwrap();
+            let board_bak = board.clone();
+
+            for mv in move_generator::all_moves(&board) {
+                let undo = board.make_move(mv.clone());
+                assert_ne!(board, board_bak, "{:?} did not change the board", mv);
+
+                board.unmake_move(mv.clone(), undo);
+                assert_eq!(board, board_bak, "{:?} was not correctly taken back", mv);
+            }
+        }
+    }
+
+    #[test]
+    fn make_move_captured_piece() {
+        let mut board = Board::from_fen("4k3/8/2n5/r7/8/8/3B4/4K3 w - - 0 1").unwrap();
+
+        let undo = board.make_move(Move::new(White, Bishop, D2, A5));
+        assert_eq!(undo.captured, Some(Rook));
+
+        let undo = board.make_move(Move::new(Black, King, E8, E7));
+        assert_eq!(undo.captured, None);
+    }
+
     #[test]
     fn do_move_switches_active_side() {
-        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 0").unwrap();
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
 
         board.do_move(Move::new(White, King, E1, E2));
-        assert_eq!(board.get_fen(), "4k3/8/8/8/8/8/4K3/8 b - - 0 0");
+        assert_eq!(board.get_fen(), "4k3/8/8/8/8/8/4K3/8 b - - 1 1");
 
         board.do_move(Move::new(Black, King, E8, E7));
-        assert_eq!(board.get_fen(), "8/4k3/8/8/8/8/4K3/8 w - - 0 0");
+        assert_eq!(board.get_fen(), "8/4k3/8/8/8/8/4K3/8 w - - 2 2");
     }
 
     #[test]
@@ -858,15 +2690,15 @@ mod tests {
         let mut board = Board::from_fen("r3k2r/1P4P1/8/8/8/8/1p4p1/R3K2R w KQkq - 0 0").unwrap();
 
         board.do_move(Move::new(White, Pawn, B7, A8));
-        assert!(!board.can_black_castle_queen_side);
+        assert!(!board.castling_rights.has(Black, QueenSide));
 
         board.do_move(Move::new(White, Pawn, G7, H8));
-        assert!(!board.can_black_castle_king_side);
+        assert!(!board.castling_rights.has(Black, KingSide));
 
         board.do_move(Move::new(Black, Pawn, B2, A1));
-        assert!(!board.can_white_castle_queen_side);
+        assert!(!board.castling_rights.has(White, QueenSide));
 
         board.do_move(Move::new(Black, Pawn, G2, H1));
-        assert!(!board.can_white_castle_king_side);
+        assert!(!board.castling_rights.has(White, KingSide));
     }
 }
@@ -0,0 +1,138 @@
+use std::fmt::Display;
+
+use crate::Color;
+
+/// The side of the board a king can castle to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CastlingSide {
+    /// Castling with the rook on the H file (`O-O`).
+    KingSide,
+    /// Castling with the rook on the A file (`O-O-O`).
+    QueenSide,
+}
+
+impl CastlingSide {
+    pub const fn all() -> [CastlingSide; 2] {
+        [CastlingSide::KingSide, CastlingSide::QueenSide]
+    }
+}
+
+/// The sides each color may still castle to.
+///
+/// Every right is a single bit, ordered by color and then side (black king side
+/// is the lowest bit).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CastlingRights(u8);
+
+impl CastlingRights {
+    /// Both colors may castle to both sides.
+    pub const ALL: CastlingRights = CastlingRights(0b1111);
+    /// No color may castle.
+    pub const NONE: CastlingRights = CastlingRights(0);
+
+    pub fn add(&mut self, color: Color, side: CastlingSide) {
+        self.0 |= Self::bit_of(color, side);
+    }
+
+    /// Removes the rights of the given color to castle to either side.
+    pub fn clear_color(&mut self, color: Color) {
+        for side in CastlingSide::all() {
+            self.remove(color, side);
+        }
+    }
+
+    /// Returns the rights with the colors swapped.
+    pub fn flip_colors(&self) -> CastlingRights {
+        CastlingRights((self.0 >> 2) | ((self.0 & 0b11) << 2))
+    }
+
+    pub fn has(&self, color: Color, side: CastlingSide) -> bool {
+        self.0 & Self::bit_of(color, side) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn remove(&mut self, color: Color, side: CastlingSide) {
+        self.0 &= !Self::bit_of(color, side);
+    }
+
+    fn bit_of(color: Color, side: CastlingSide) -> u8 {
+        1 << (color as u8 * 2 + side as u8)
+    }
+}
+
+/// Formats the rights the way they are written in FEN strings, e.g. `KQkq`, or
+/// `-` if there are none.
+impl Display for CastlingRights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "-");
+        }
+
+        for (color, side, c) in [
+            (Color::White, CastlingSide::KingSide, 'K'),
+            (Color::White, CastlingSide::QueenSide, 'Q'),
+            (Color::Black, CastlingSide::KingSide, 'k'),
+            (Color::Black, CastlingSide::QueenSide, 'q'),
+        ] {
+            if self.has(color, side) {
+                write!(f, "{}", c)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use CastlingSide::*;
+    use Color::*;
+
+    #[test]
+    fn display() {
+        let mut rights = CastlingRights::NONE;
+
+        assert_eq!(rights.to_string(), "-");
+
+        rights.add(Black, QueenSide);
+        rights.add(White, KingSide);
+
+        assert_eq!(rights.to_string(), "Kq");
+        assert_eq!(CastlingRights::ALL.to_string(), "KQkq");
+    }
+
+    #[test]
+    fn remove_and_clear_color() {
+        let mut rights = CastlingRights::ALL;
+
+        rights.remove(White, QueenSide);
+
+        assert!(!rights.has(White, QueenSide));
+        assert!(rights.has(White, KingSide));
+        assert_eq!(rights.to_string(), "Kkq");
+
+        rights.clear_color(Black);
+
+        assert!(!rights.has(Black, KingSide));
+        assert!(!rights.has(Black, QueenSide));
+        assert_eq!(rights.to_string(), "K");
+
+        rights.remove(White, KingSide);
+
+        assert!(rights.is_empty());
+    }
+
+    #[test]
+    fn flip_colors() {
+        let mut rights = CastlingRights::NONE;
+        rights.add(White, KingSide);
+        rights.add(Black, QueenSide);
+
+        assert_eq!(rights.flip_colors().to_string(), "Qk");
+        assert_eq!(CastlingRights::ALL.flip_colors(), CastlingRights::ALL);
+    }
+}
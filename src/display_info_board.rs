@@ -0,0 +1,119 @@
+use crate::{
+    board::{BoardPos, PieceInstance},
+    info_board::{InfoBoard, PosInfo},
+    Board, Color,
+};
+
+const BG_DARK: &str = "\u{001b}[48;5;94m";
+const BG_HIT: &str = "\u{001b}[48;5;160m";
+const BG_LIGHT: &str = "\u{001b}[48;5;180m";
+const BG_MOVE: &str = "\u{001b}[48;5;71m";
+const FG_BLACK: &str = "\u{001b}[30m";
+const FG_WHITE: &str = "\u{001b}[97m";
+const RESET: &str = "\u{001b}[0m";
+
+impl InfoBoard {
+    /// Renders the board with ANSI escape sequences, for display in a terminal.
+    ///
+    /// Squares marked as [`PosInfo::Move`] or [`PosInfo::PieceHit`] are
+    /// highlighted.
+    pub fn to_ansi(&self) -> String {
+        let poses = self.poses();
+
+        ansi_grid(|i| {
+            let bg = match poses[i] {
+                PosInfo::Move => Some(BG_MOVE),
+                PosInfo::PieceHit(_) => Some(BG_HIT),
+                _ => None,
+            };
+            let content = match &poses[i] {
+                PosInfo::Piece(ins) | PosInfo::PieceHit(ins) => {
+                    format!("{} {} ", get_fg_color_of(ins), get_piece_symbol_at(self, i))
+                }
+                PosInfo::Move | PosInfo::None => "   ".to_owned(),
+            };
+
+            (bg, content)
+        })
+    }
+}
+
+/// Renders a board with ANSI escape sequences, labeling the ranks and files.
+///
+/// `square` returns the content of the square with the given index, which should
+/// be three characters wide, and optionally a background replacing the light or
+/// dark one of the square.
+pub(crate) fn ansi_grid(mut square: impl FnMut(usize) -> (Option<&'static str>, String)) -> String {
+    let mut val = String::new();
+
+    for i in 0..Board::SIZE {
+        let file = i % Board::HEIGHT;
+        let rank = i / Board::HEIGHT;
+
+        if file == 0 {
+            val += &format!("{} ", Board::HEIGHT - rank);
+        }
+
+        let (bg, content) = square(i);
+
+        val += bg.unwrap_or(if (file + rank).is_multiple_of(2) {
+            BG_LIGHT
+        } else {
+            BG_DARK
+        });
+        val += &content;
+
+        if file == 7 {
+            val += RESET;
+            val += "\n";
+        }
+    }
+
+    val += "   a  b  c  d  e  f  g  h";
+
+    val
+}
+
+fn get_fg_color_of(ins: &PieceInstance) -> &'static str {
+    match ins.color {
+        Color::Black => FG_BLACK,
+        Color::White => FG_WHITE,
+    }
+}
+
+fn get_piece_symbol_at(info_board: &InfoBoard, pos: impl BoardPos) -> &str {
+    match info_board.get(pos) {
+        PosInfo::Piece(ins) | PosInfo::PieceHit(ins) => ins.piece.symbol(ins.color),
+        PosInfo::Move | PosInfo::None => " ",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Square;
+
+    use super::*;
+
+    #[test]
+    fn to_ansi_foreground_matches_piece_color() {
+        let board = Board::new_with_standard_formation();
+        let rendered = InfoBoard::from(&board).to_ansi();
+        let rows = rendered.lines().collect::<Vec<_>>();
+
+        // The back ranks contain only pieces of a single color.
+        assert_eq!(rows[0].matches(FG_BLACK).count(), 8);
+        assert_eq!(rows[0].matches(FG_WHITE).count(), 0);
+        assert_eq!(rows[7].matches(FG_WHITE).count(), 8);
+        assert_eq!(rows[7].matches(FG_BLACK).count(), 0);
+        assert!(rows[7].contains(&format!("{} {} ", FG_WHITE, "♚")));
+    }
+
+    #[test]
+    fn to_ansi_highlights_moves() {
+        let board = Board::new_with_standard_formation();
+        let rendered = InfoBoard::with_moves_from(&board, Square::G1).to_ansi();
+
+        assert_eq!(rendered.matches(BG_MOVE).count(), 2);
+        assert_eq!(rendered.matches(BG_HIT).count(), 0);
+    }
+}
@@ -1,50 +1,359 @@
 use std::ops::Index;
 
-use crate::{bit_board::SetBitsIter, Board, Color, Piece};
+use crate::{
+    bit_board::{self, SetBitsIter},
+    move_generator, Board, Color, Piece,
+};
 
 use Color::*;
-use Piece::*;
 
 /// Contains the material values of all pieces.
+///
+/// The king can never be captured, so it has no material value. Losing it is
+/// covered by the checkmate detection of the search instead.
 pub const MAT_VAL: MatValTbl = MatValTbl([
-    3,       /* Bishop */
-    i8::MAX, /* King */
-    3,       /* Knight */
-    1,       /* Pawn */
-    9,       /* Queen */
-    5,       /* Rook */
+    3, /* Bishop */
+    0, /* King */
+    3, /* Knight */
+    1, /* Pawn */
+    9, /* Queen */
+    5, /* Rook */
+]);
+
+/// Penalty in centipawns for every additional pawn on the same file.
+pub const DOUBLED_PAWN_PENALTY: i32 = 10;
+/// Penalty in centipawns for a pawn without friendly pawns on the adjacent files.
+pub const ISOLATED_PAWN_PENALTY: i32 = 15;
+/// Bonus in centipawns for a passed pawn, indexed by its rank relative to the
+/// owning side (`0` = own back rank).
+pub const PASSED_PAWN_BONUS: [i32; Board::HEIGHT] = [0, 5, 10, 20, 35, 60, 100, 0];
+
+/// Bonus in centipawns for a pawn shielding a castled king (see [`king_safety`]),
+/// indexed by how many ranks it is in front of the king, minus one.
+pub const PAWN_SHIELD_BONUS: [i32; 2] = [15, 5];
+
+/// The [`game_phase`] of a position with all pieces still on the board.
+pub const MAX_GAME_PHASE: u8 = 24;
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+
+/// Contains the (middle game) piece-square tables of all pieces.
+///
+/// The tables are from the perspective of [`White`], using the same index layout
+/// as the board (the first entry is A8). For [`Black`], they are mirrored
+/// vertically. Values are in centipawns.
+///
+/// Taken from: https://www.chessprogramming.org/Simplified_Evaluation_Function
+#[rustfmt::skip]
+pub const PST: PstTbl = PstTbl([
+    // Bishop
+    [
+        -20, -10, -10, -10, -10, -10, -10, -20,
+        -10,   0,   0,   0,   0,   0,   0, -10,
+        -10,   0,   5,  10,  10,   5,   0, -10,
+        -10,   5,   5,  10,  10,   5,   5, -10,
+        -10,   0,  10,  10,  10,  10,   0, -10,
+        -10,  10,  10,  10,  10,  10,  10, -10,
+        -10,   5,   0,   0,   0,   0,   5, -10,
+        -20, -10, -10, -10, -10, -10, -10, -20,
+    ],
+    // King (middle game)
+    [
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -20, -30, -30, -40, -40, -30, -30, -20,
+        -10, -20, -20, -20, -20, -20, -20, -10,
+         20,  20,   0,   0,   0,   0,  20,  20,
+         20,  30,  10,   0,   0,  10,  30,  20,
+    ],
+    // Knight
+    [
+        -50, -40, -30, -30, -30, -30, -40, -50,
+        -40, -20,   0,   0,   0,   0, -20, -40,
+        -30,   0,  10,  15,  15,  10,   0, -30,
+        -30,   5,  15,  20,  20,  15,   5, -30,
+        -30,   0,  15,  20,  20,  15,   0, -30,
+        -30,   5,  10,  15,  15,  10,   5, -30,
+        -40, -20,   0,   5,   5,   0, -20, -40,
+        -50, -40, -30, -30, -30, -30, -40, -50,
+    ],
+    // Pawn
+    [
+          0,   0,   0,   0,   0,   0,   0,   0,
+         50,  50,  50,  50,  50,  50,  50,  50,
+         10,  10,  20,  30,  30,  20,  10,  10,
+          5,   5,  10,  25,  25,  10,   5,   5,
+          0,   0,   0,  20,  20,   0,   0,   0,
+          5,  -5, -10,   0,   0, -10,  -5,   5,
+          5,  10,  10, -20, -20,  10,  10,   5,
+          0,   0,   0,   0,   0,   0,   0,   0,
+    ],
+    // Queen
+    [
+        -20, -10, -10,  -5,  -5, -10, -10, -20,
+        -10,   0,   0,   0,   0,   0,   0, -10,
+        -10,   0,   5,   5,   5,   5,   0, -10,
+         -5,   0,   5,   5,   5,   5,   0,  -5,
+          0,   0,   5,   5,   5,   5,   0,  -5,
+        -10,   5,   5,   5,   5,   5,   0, -10,
+        -10,   0,   5,   0,   0,   0,   0, -10,
+        -20, -10, -10,  -5,  -5, -10, -10, -20,
+    ],
+    // Rook
+    [
+          0,   0,   0,   0,   0,   0,   0,   0,
+          5,  10,  10,  10,  10,  10,  10,   5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+          0,   0,   0,   5,   5,   0,   0,   0,
+    ],
 ]);
 
+/// Same as [`PST`], but for the end game, where the king should be centralized.
+#[rustfmt::skip]
+pub const PST_EG: PstTbl = {
+    let mut tables = PST.0;
+
+    tables[Piece::King as usize] = [
+        -50, -40, -30, -20, -20, -30, -40, -50,
+        -30, -20, -10,   0,   0, -10, -20, -30,
+        -30, -10,  20,  30,  30,  20, -10, -30,
+        -30, -10,  30,  40,  40,  30, -10, -30,
+        -30, -10,  30,  40,  40,  30, -10, -30,
+        -30, -10,  20,  30,  30,  20, -10, -30,
+        -30, -30,   0,   0,   0,   0, -30, -30,
+        -50, -30, -30, -30, -30, -30, -30, -50,
+    ];
+
+    PstTbl(tables)
+};
+
 /// Scores the board so it can later be used in a min-max algorithm.
 ///
 /// [`Black`] received pieces decrease the overall score, while [`White`] increases
-/// it.
+/// it. The score is in centipawns, combining the material ([`MAT_VAL`]), the
+/// placement of the pieces ([`PST`] and [`PST_EG`], tapered by the [`game_phase`]),
+/// the [`pawn_structure_score`], and the [`king_safety`].
 pub fn evaluate(board: &Board) -> i32 {
+    evaluate_with(board, &EvalWeights::default())
+}
+
+/// Same as [`evaluate`], but from the perspective of the side to move.
+///
+/// Positive scores are good for the side to move, which is the convention of the
+/// negamax algorithm.
+pub fn evaluate_relative(board: &Board) -> i32 {
+    match board.side_to_move() {
+        Color::Black => -evaluate(board),
+        Color::White => evaluate(board),
+    }
+}
+
+/// Same as [`evaluate`], but with custom weights for the optional terms.
+pub fn evaluate_with(board: &Board, weights: &EvalWeights) -> i32 {
     let mut val = 0;
 
+    if weights.mobility != 0 {
+        val += weights.mobility * mobility(board);
+    }
+
+    let mut mg_val = 0;
+    let mut eg_val = 0;
+
     for color in [Black, White] {
-        for (mat_val, bit_board) in [
-            (MAT_VAL[Bishop], board.bishops[color]),
-            (MAT_VAL[King], board.king[color]),
-            (MAT_VAL[Pawn], board.pawns[color]),
-            (MAT_VAL[Queen], board.queens[color]),
-            (MAT_VAL[Rook], board.rooks[color]),
-        ] {
-            for _ in SetBitsIter(bit_board) {
-                if color == White {
-                    val += mat_val as i32;
-                } else {
-                    val -= mat_val as i32;
-                }
+        let sign = if color == White { 1 } else { -1 };
+
+        for piece in Piece::all() {
+            for pos in SetBitsIter(board.bit_boards_of(piece)[color]) {
+                let mat_val = MAT_VAL[piece] as i32 * 100;
+
+                mg_val += sign * (mat_val + PST.get(piece, color, pos) as i32);
+                eg_val += sign * (mat_val + PST_EG.get(piece, color, pos) as i32);
             }
         }
     }
 
-    val
+    // Blend the middle and end game scores, depending on the remaining material.
+    let phase = game_phase(board) as i32;
+    val += (mg_val * phase + eg_val * (MAX_GAME_PHASE as i32 - phase)) / MAX_GAME_PHASE as i32;
+
+    val + pawn_structure_score(board) + king_safety(board, White) - king_safety(board, Black)
+}
+
+/// Estimates how far the game has progressed, based on the remaining non-pawn
+/// material.
+///
+/// Ranges from `0` (end game) to [`MAX_GAME_PHASE`] (middle game). Knights and
+/// bishops count `1`, rooks `2`, and queens `4`.
+pub fn game_phase(board: &Board) -> u8 {
+    let count = board.material_count();
+    let phase: usize = [
+        (Piece::Bishop, 1),
+        (Piece::Knight, 1),
+        (Piece::Rook, 2),
+        (Piece::Queen, 4),
+    ]
+    .iter()
+    .map(|(piece, weight)| {
+        (count[Black][*piece as usize] + count[White][*piece as usize]) as usize * weight
+    })
+    .sum();
+
+    // Promotions can lead to more material than at the start.
+    phase.min(MAX_GAME_PHASE as usize) as u8
+}
+
+/// Scores the pawn shield of the king of the given color in centipawns, positive
+/// being good for that color.
+///
+/// Only a castled king, i.e. one on its back rank on the king or queen side
+/// (not on the D or E file), is considered. Every friendly pawn on the king's
+/// file or the adjacent ones, one or two ranks in front of the king, is rewarded
+/// (see [`PAWN_SHIELD_BONUS`]). The score is tapered by the [`game_phase`], so it
+/// only matters in the middle game.
+pub fn king_safety(board: &Board, color: Color) -> i32 {
+    let Some(king_pos) = board.king_square(color).map(usize::from) else {
+        return 0;
+    };
+
+    let file = king_pos % Board::WIDTH;
+    let rank = king_pos / Board::WIDTH;
+    let back_rank = match color {
+        Black => 0,
+        White => Board::HEIGHT - 1,
+    };
+
+    if rank != back_rank || (3..=4).contains(&file) {
+        return 0;
+    }
+
+    let files = (file.saturating_sub(1)..=(file + 1).min(Board::WIDTH - 1))
+        .fold(0, |mask, file| mask | (FILE_A << file));
+    let mut val = 0;
+
+    for (distance, bonus) in PAWN_SHIELD_BONUS.iter().enumerate() {
+        let shield_rank = match color {
+            Black => rank + distance + 1,
+            White => rank - distance - 1,
+        };
+        let rank_mask = 0xffu64 << (shield_rank * Board::WIDTH);
+
+        val += bit_board::count_set_bits(board.pawns[color] & files & rank_mask) as i32 * bonus;
+    }
+
+    val * game_phase(board) as i32 / MAX_GAME_PHASE as i32
+}
+
+/// Counts the pseudo-legal moves of [`White`] minus the ones of [`Black`].
+pub fn mobility(board: &Board) -> i32 {
+    // The moves are generated for the side to move, so the turn is flipped to
+    // count the moves of both sides.
+    let mut board = board.clone();
+
+    board.set_side_to_move(White);
+    let white_moves = move_generator::all_moves(&board).len() as i32;
+    board.set_side_to_move(Black);
+    let black_moves = move_generator::all_moves(&board).len() as i32;
+
+    white_moves - black_moves
+}
+
+/// Scores the pawn structure in centipawns, [`White`] positive.
+///
+/// Doubled (see [`DOUBLED_PAWN_PENALTY`]) and isolated pawns (see
+/// [`ISOLATED_PAWN_PENALTY`]) are penalized, while passed pawns (see
+/// [`PASSED_PAWN_BONUS`]) are rewarded.
+pub fn pawn_structure_score(board: &Board) -> i32 {
+    return pawn_structure_of(board, White) - pawn_structure_of(board, Black);
+
+    fn pawn_structure_of(board: &Board, color: Color) -> i32 {
+        let pawns = board.pawns[color];
+        let opp_pawns = board.pawns[color.opposing()];
+        let mut val = 0;
+
+        for file in 0..Board::WIDTH {
+            let on_file = bit_board::count_set_bits(pawns & (FILE_A << file)) as i32;
+
+            if on_file > 1 {
+                val -= (on_file - 1) * DOUBLED_PAWN_PENALTY;
+            }
+        }
+
+        for pos in SetBitsIter(pawns) {
+            let file = pos % Board::WIDTH;
+            let rank = pos / Board::WIDTH;
+
+            if pawns & adjacent_files(file) == 0 {
+                val -= ISOLATED_PAWN_PENALTY;
+            }
+
+            let files = adjacent_files(file) | (FILE_A << file);
+            // The ranks in front of the pawn, remember that rank `0` is the 8th one.
+            let ranks_ahead = match color {
+                White => (1u64 << (rank * Board::WIDTH)) - 1,
+                Black => u64::MAX
+                    .checked_shl(((rank + 1) * Board::WIDTH) as u32)
+                    .unwrap_or(0),
+            };
+
+            if opp_pawns & files & ranks_ahead == 0 {
+                let relative_rank = match color {
+                    White => Board::HEIGHT - 1 - rank,
+                    Black => rank,
+                };
+
+                val += PASSED_PAWN_BONUS[relative_rank];
+            }
+        }
+
+        val
+    }
+
+    fn adjacent_files(file: usize) -> u64 {
+        let mut mask = 0;
+
+        if file > 0 {
+            mask |= FILE_A << (file - 1);
+        }
+        if file < Board::WIDTH - 1 {
+            mask |= FILE_A << (file + 1);
+        }
+
+        mask
+    }
+}
+
+/// The weights of the optional terms of [`evaluate_with`].
+///
+/// By default, all optional terms are disabled.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvalWeights {
+    /// Centipawns per pseudo-legal move more than the opponent (see [`mobility`]).
+    pub mobility: i32,
 }
 
 pub struct MatValTbl([i8; 6]);
 
+pub struct PstTbl([[i8; Board::SIZE]; 6]);
+
+impl PstTbl {
+    /// Returns the value of the piece of the given color on the given square.
+    pub fn get(&self, piece: Piece, color: Color, pos: usize) -> i8 {
+        // Flips the rank, mirroring the table for black.
+        let pos = match color {
+            Black => pos ^ 56,
+            White => pos,
+        };
+
+        self.0[piece as usize][pos]
+    }
+}
+
 impl Index<Piece> for MatValTbl {
     type Output = i8;
 
@@ -59,39 +368,220 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn symmetric() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+            let flipped = board.flip_vertical();
+
+            assert_eq!(evaluate(&board), -evaluate(&flipped), "{}", fen);
+            assert_eq!(
+                evaluate_relative(&board),
+                evaluate_relative(&flipped),
+                "{}",
+                fen
+            );
+        }
+    }
+
     #[test]
     fn bishop() {
         let board = Board::from_fen("8/8/8/8/8/8/8/2B2B2 w - - 0 0").unwrap();
 
-        assert_eq!(evaluate(&board), 6);
+        assert_eq!(evaluate(&board), 600 - 10 - 10);
     }
 
     #[test]
     fn king() {
         let board = Board::from_fen("8/8/8/8/8/8/8/4K3 w - - 0 0").unwrap();
 
-        assert_eq!(evaluate(&board), i8::MAX as i32);
+        // Without other pieces, only the end game table is used.
+        assert_eq!(evaluate(&board), PST_EG.get(Piece::King, White, 60) as i32);
+    }
+
+    #[test]
+    fn multiple_kings() {
+        // Kings used to be worth `i8::MAX`, which swamped everything else.
+        let board = Board::from_fen("8/8/8/8/8/8/8/3KK2R w - - 0 1").unwrap();
+
+        assert!((400..600).contains(&evaluate(&board)));
+    }
+
+    #[test]
+    fn game_phase_starting_position() {
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(game_phase(&board), MAX_GAME_PHASE);
+    }
+
+    #[test]
+    fn game_phase_pawn_ending() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(game_phase(&board), 0);
+    }
+
+    #[test]
+    fn tapered_king_centralization() {
+        let center = Board::from_fen("8/8/8/8/4K3/8/8/8 w - - 0 1").unwrap();
+        let corner = Board::from_fen("8/8/8/8/8/8/8/6K1 w - - 0 1").unwrap();
+
+        // In the end game, the king should be centralized.
+        assert!(evaluate(&center) > evaluate(&corner));
+
+        let center = Board::from_fen("rnbqkbnr/8/8/8/4K3/8/8/RNBQ1BNR w - - 0 1").unwrap();
+        let corner = Board::from_fen("rnbqkbnr/8/8/8/8/8/6K1/RNBQ1BNR w - - 0 1").unwrap();
+
+        // While in the middle game, it should stay safe in the corner.
+        assert!(evaluate(&center) < evaluate(&corner));
     }
 
     #[test]
     fn pawn() {
         let board = Board::from_fen("8/8/8/8/8/8/PPPPPPPP/8 w - - 0 0").unwrap();
 
-        assert_eq!(evaluate(&board), 8);
+        // Without opposing pawns, all the pawns are passed.
+        assert_eq!(
+            evaluate(&board),
+            800 + 5 + 10 + 10 - 20 - 20 + 10 + 10 + 5 + 8 * PASSED_PAWN_BONUS[1]
+        );
     }
 
     #[test]
     fn queen() {
         let board = Board::from_fen("8/8/8/8/8/8/8/3Q4 w - - 0 0").unwrap();
 
-        assert_eq!(evaluate(&board), 9);
+        assert_eq!(evaluate(&board), 900 - 5);
     }
 
     #[test]
     fn rook() {
         let board = Board::from_fen("8/8/8/8/8/8/8/R6R w - - 0 0").unwrap();
 
-        assert_eq!(evaluate(&board), 10);
+        assert_eq!(evaluate(&board), 1000);
+    }
+
+    #[test]
+    fn knight_centralized() {
+        let center = Board::from_fen("8/8/8/8/3N4/8/8/8 w - - 0 1").unwrap();
+        let rim = Board::from_fen("8/8/8/8/N7/8/8/8 w - - 0 1").unwrap();
+
+        assert!(evaluate(&center) > evaluate(&rim));
+    }
+
+    #[test]
+    fn pst_mirrored_for_black() {
+        let white = Board::from_fen("8/8/8/8/3N4/8/8/8 w - - 0 1").unwrap();
+        let black = Board::from_fen("8/8/8/3n4/8/8/8/8 w - - 0 1").unwrap();
+
+        assert_eq!(evaluate(&white), -evaluate(&black));
+    }
+
+    #[test]
+    fn king_safety_intact_shield() {
+        let intact =
+            Board::from_fen("rnbq1rk1/pppppppp/8/8/8/8/PPPPPPPP/RNBQ1RK1 w - - 0 1").unwrap();
+        let advanced =
+            Board::from_fen("rnbq1rk1/pppppppp/8/8/6PP/5P2/PPPPP3/RNBQ1RK1 w - - 0 1").unwrap();
+
+        assert_eq!(
+            king_safety(&intact, White),
+            3 * PAWN_SHIELD_BONUS[0] * game_phase(&intact) as i32 / MAX_GAME_PHASE as i32
+        );
+        assert!(king_safety(&intact, White) > king_safety(&advanced, White));
+        assert_eq!(king_safety(&intact, Black), king_safety(&intact, White));
+        assert!(evaluate(&intact) > evaluate(&advanced));
+    }
+
+    #[test]
+    fn king_safety_only_castled_and_middle_game() {
+        let uncastled =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let end_game = Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+
+        assert_eq!(king_safety(&uncastled, White), 0);
+        assert_eq!(king_safety(&end_game, White), 0);
+    }
+
+    #[test]
+    fn mobility_open_position() {
+        let open = Board::from_fen("4k3/8/8/8/3B4/8/6P1/4K3 w - - 0 1").unwrap();
+        let cramped = Board::from_fen("4k3/8/8/8/8/8/6P1/4K2B w - - 0 1").unwrap();
+        let weights = EvalWeights { mobility: 10 };
+
+        assert!(mobility(&open) > mobility(&cramped));
+        assert!(evaluate_with(&open, &weights) > evaluate_with(&cramped, &weights));
+    }
+
+    #[test]
+    fn mobility_independent_of_side_to_move() {
+        let white = Board::from_fen("4k3/8/8/8/3B4/8/6P1/4K3 w - - 0 1").unwrap();
+        let black = Board::from_fen("4k3/8/8/8/3B4/8/6P1/4K3 b - - 0 1").unwrap();
+
+        assert_eq!(mobility(&white), mobility(&black));
+        assert_eq!(mobility(&Board::new_with_standard_formation()), 0);
+    }
+
+    #[test]
+    fn pawn_structure_doubled() {
+        let doubled = Board::from_fen("8/8/8/8/8/3P4/3PP3/8 w - - 0 1").unwrap();
+        let not_doubled = Board::from_fen("8/8/8/8/8/8/2PPP3/8 w - - 0 1").unwrap();
+
+        assert_eq!(
+            pawn_structure_score(&doubled) - pawn_structure_score(&not_doubled),
+            -DOUBLED_PAWN_PENALTY - PASSED_PAWN_BONUS[1] + PASSED_PAWN_BONUS[2]
+        );
+    }
+
+    #[test]
+    fn pawn_structure_isolated() {
+        let board = Board::from_fen("8/pp6/8/8/8/8/P1P5/8 w - - 0 1").unwrap();
+
+        // None of the pawns are passed, but only the white ones are isolated.
+        assert_eq!(pawn_structure_score(&board), -2 * ISOLATED_PAWN_PENALTY);
+    }
+
+    #[test]
+    fn pawn_structure_passed() {
+        // Both pawns are isolated, but the white one is further advanced.
+        let board = Board::from_fen("8/p7/4P3/8/8/8/8/8 w - - 0 1").unwrap();
+
+        assert_eq!(
+            pawn_structure_score(&board),
+            PASSED_PAWN_BONUS[5] - PASSED_PAWN_BONUS[1]
+        );
+    }
+
+    #[test]
+    fn pawn_structure_blocked_not_passed() {
+        let board = Board::from_fen("8/8/3p4/8/4P3/8/8/8 w - - 0 1").unwrap();
+
+        // Both pawns are isolated, and neither of them is passed.
+        assert_eq!(pawn_structure_score(&board), 0);
+    }
+
+    #[test]
+    fn relative_side_ahead_to_move() {
+        let white = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+        let black = Board::from_fen("3qk3/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+
+        assert!(evaluate_relative(&white) > 0);
+        assert!(evaluate_relative(&black) > 0);
+        assert_eq!(evaluate_relative(&white), evaluate_relative(&black));
+    }
+
+    #[test]
+    fn relative_side_behind_to_move() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+
+        assert_eq!(evaluate_relative(&board), -evaluate(&board));
+        assert!(evaluate_relative(&board) < 0);
     }
 
     #[test]
@@ -1,11 +1,63 @@
-use crate::{board::PieceInstance, square::Square, Board, Color, Piece};
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+use crate::{
+    board::PieceInstance,
+    castling_rights::{CastlingRights, CastlingSide},
+    square::Square,
+    Board, Color, Piece,
+};
+
+/// The reasons parsing a FEN string may fail.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FenError {
+    BadPieceChar(char),
+    BadSideToMove(String),
+    BadCastling(String),
+    BadSquare(String),
+    BadClock(String),
+    BadPlacement(String),
+    /// The field with the given (zero based) index is missing.
+    MissingField(usize),
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FenError::BadPieceChar(c) => write!(f, "cannot convert from '{}' to piece", c),
+            FenError::BadSideToMove(side_to_move) => write!(
+                f,
+                "failed to parse whose turn it is, expected 'b' or 'w' but received {}",
+                side_to_move
+            ),
+            FenError::BadCastling(castling_rights) => write!(
+                f,
+                "failed to parse the castling rights, expected '-' or any of 'KQkq' (or 'HAha' for Shredder-FEN) but received {}",
+                castling_rights
+            ),
+            FenError::BadSquare(sym) => {
+                write!(f, "could not identify square with symbol '{}'", sym)
+            }
+            FenError::BadClock(clock) => write!(
+                f,
+                "failed to parse the move counter, expected a number but received {}",
+                clock
+            ),
+            FenError::BadPlacement(reason) => {
+                write!(f, "failed to parse the piece placement, {}", reason)
+            }
+            FenError::MissingField(idx) => write!(f, "the field at index {} is missing", idx),
+        }
+    }
+}
+
+impl Error for FenError {}
 
 /// An interface to convert a playing board to and from a fen string.
 ///
 /// For more information, visit: https://www.chess.com/terms/fen-chess
 pub trait Fen: Sized {
     fn get_fen(&self) -> String;
-    fn from_fen(fen: &str) -> Result<Self, String>;
+    fn from_fen(fen: &str) -> Result<Self, FenError>;
 }
 
 impl Fen for PieceInstance {
@@ -27,7 +79,7 @@ impl Fen for PieceInstance {
         .to_owned()
     }
 
-    fn from_fen(fen: &str) -> Result<PieceInstance, String> {
+    fn from_fen(fen: &str) -> Result<PieceInstance, FenError> {
         Ok(match fen {
             "B" => PieceInstance::new(Color::White, Piece::Bishop),
             "K" => PieceInstance::new(Color::White, Piece::King),
@@ -41,11 +93,18 @@ impl Fen for PieceInstance {
             "p" => PieceInstance::new(Color::Black, Piece::Pawn),
             "q" => PieceInstance::new(Color::Black, Piece::Queen),
             "r" => PieceInstance::new(Color::Black, Piece::Rook),
-            unknown => return Err(format!("cannot convert from '{}' to piece", unknown)),
+            unknown => {
+                return Err(FenError::BadPieceChar(
+                    unknown.chars().next().unwrap_or_default(),
+                ))
+            }
         })
     }
 }
 
+/// The FEN of the standard chess starting position.
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
 #[rustfmt::skip]
 pub const FEN_SQUARE_SYMBOL_LOOKUP: [&str; 64] = [
     "a8", "b8", "c8", "d8", "e8", "f8", "g8", "h8",
@@ -63,13 +122,12 @@ impl Fen for Square {
         FEN_SQUARE_SYMBOL_LOOKUP[*self as usize].to_owned()
     }
 
-    fn from_fen(fen: &str) -> Result<Self, String> {
-        let idx = FEN_SQUARE_SYMBOL_LOOKUP
+    fn from_fen(fen: &str) -> Result<Self, FenError> {
+        FEN_SQUARE_SYMBOL_LOOKUP
             .iter()
             .position(|sym| sym == &fen)
-            .ok_or_else(|| format!("could not identify square with symbol '{}'", fen))?;
-
-        idx.try_into()
+            .and_then(|idx| idx.try_into().ok())
+            .ok_or_else(|| FenError::BadSquare(fen.to_owned()))
     }
 }
 
@@ -81,8 +139,8 @@ impl Fen for Board {
             side_to_move(self),
             castling_abilities(self),
             en_passant_target(self),
-            halve_move_clock(),
-            full_move_counter()
+            halve_move_clock(self),
+            full_move_counter(self)
         );
 
         fn pieces(board: &Board) -> String {
@@ -121,125 +179,159 @@ impl Fen for Board {
         }
 
         fn side_to_move(board: &Board) -> String {
-            if board.is_whites_turn { "w" } else { "b" }.to_owned()
+            board.side_to_move().to_fen_char().to_string()
         }
 
         fn castling_abilities(board: &Board) -> String {
-            let mut val = String::new();
-
-            if board.can_white_castle_king_side {
-                val.push('K');
-            }
-            if board.can_white_castle_queen_side {
-                val.push('Q');
-            }
-            if board.can_black_castle_king_side {
-                val.push('k');
-            }
-            if board.can_black_castle_queen_side {
-                val.push('q');
-            }
-
-            if val.len() == 0 {
-                val = "-".to_owned();
-            }
-
-            val
+            board.castling_rights().to_string()
         }
 
         fn en_passant_target(board: &Board) -> String {
-            if let Some(idx) = board.en_passant_target_idx {
-                return Square::try_from(idx)
-                    .unwrap_or_else(|err| {
-                        panic!("couldn't convert en passant index to square: {}", err)
-                    })
-                    .get_fen();
+            if let Some(sq) = board.en_passant_square() {
+                return sq.get_fen();
             }
 
             "-".to_owned()
         }
 
-        fn halve_move_clock() -> String {
-            // (At least right now) this feature is not relevant and we just return
-            // "0" to get a valid FEN.
-            "0".to_owned()
+        fn halve_move_clock(board: &Board) -> String {
+            board.half_move_clock.to_string()
         }
 
-        fn full_move_counter() -> String {
-            // (At least right now) this feature is not relevant and we just return
-            // "0" to get a valid FEN.
-            "0".to_owned()
+        fn full_move_counter(board: &Board) -> String {
+            board.full_move_number.to_string()
         }
     }
 
-    fn from_fen(fen: &str) -> Result<Board, String> {
-        let fen: Vec<_> = fen.split(' ').collect();
+    fn from_fen(fen: &str) -> Result<Board, FenError> {
+        let fen: Vec<_> = fen.split_whitespace().collect();
+        let field = |idx: usize| fen.get(idx).copied().ok_or(FenError::MissingField(idx));
 
         let mut board = Board::new_empty();
 
-        pieces(fen[0], &mut board)?;
-        side_to_move(fen[1], &mut board)?;
-        castling_rights(fen[2], &mut board);
-        en_passant_pos(fen[3], &mut board)?;
+        pieces(field(0)?, &mut board)?;
+        side_to_move(field(1)?, &mut board)?;
+        castling_rights(field(2)?, &mut board)?;
+        en_passant_pos(field(3)?, &mut board)?;
+        half_move_clock(fen.get(4), &mut board)?;
+        full_move_number(fen.get(5), &mut board)?;
 
         return Ok(board);
 
-        fn pieces(pieces: &str, board: &mut Board) -> Result<(), String> {
-            let mut idx: usize = 0;
+        fn pieces(pieces: &str, board: &mut Board) -> Result<(), FenError> {
+            let ranks: Vec<_> = pieces.split('/').collect();
 
-            for c in pieces.chars() {
-                if c == '/' {
-                    continue;
-                }
+            if ranks.len() != Board::HEIGHT {
+                return Err(FenError::BadPlacement(format!(
+                    "expected {} ranks but received {}",
+                    Board::HEIGHT,
+                    ranks.len()
+                )));
+            }
 
-                if let Some(empty_squares) = c.to_digit(10) {
-                    idx += empty_squares as usize;
+            for (rank_idx, rank) in ranks.iter().enumerate() {
+                let mut file_idx: usize = 0;
 
-                    continue;
-                }
+                for c in rank.chars() {
+                    if let Some(empty_squares) = c.to_digit(10) {
+                        file_idx += empty_squares as usize;
+                    } else {
+                        let ins: PieceInstance = Fen::from_fen(&c.to_string())?;
 
-                let ins: PieceInstance = Fen::from_fen(&c.to_string())?;
+                        if file_idx < Board::WIDTH {
+                            board.set_piece(rank_idx * Board::WIDTH + file_idx, ins);
+                        }
+                        file_idx += 1;
+                    }
 
-                board.set(ins.color, ins.piece, idx);
-                idx += 1;
+                    if file_idx > Board::WIDTH {
+                        break;
+                    }
+                }
+
+                if file_idx != Board::WIDTH {
+                    return Err(FenError::BadPlacement(format!(
+                        "expected rank '{}' to describe {} files",
+                        rank,
+                        Board::WIDTH
+                    )));
+                }
             }
 
             Ok(())
         }
 
-        fn side_to_move(side_to_move: &str, board: &mut Board) -> Result<(), String> {
-            board.is_whites_turn = match side_to_move {
-                "b" => false,
-                "w" => true,
-                _ => {
-                    return Err(format!(
-                        "failed to parse whose turn it is, expected 'b' or 'w' but received {}",
-                        side_to_move
-                    ))
-                }
-            };
+        fn side_to_move(side_to_move: &str, board: &mut Board) -> Result<(), FenError> {
+            board.set_side_to_move(match side_to_move {
+                "b" => Color::Black,
+                "w" => Color::White,
+                _ => return Err(FenError::BadSideToMove(side_to_move.to_owned())),
+            });
 
             Ok(())
         }
 
-        fn castling_rights(castling_rights: &str, board: &mut Board) {
-            if castling_rights.contains('K') {
-                board.can_white_castle_king_side = true;
-            }
-            if castling_rights.contains('Q') {
-                board.can_white_castle_queen_side = true;
+        fn castling_rights(castling_rights: &str, board: &mut Board) -> Result<(), FenError> {
+            if castling_rights == "-" {
+                return Ok(());
             }
-            if castling_rights.contains('k') {
-                board.can_black_castle_king_side = true;
+
+            if castling_rights.is_empty() {
+                return Err(FenError::BadCastling(castling_rights.to_owned()));
             }
-            if castling_rights.contains('q') {
-                board.can_black_castle_queen_side = true;
+
+            let mut rights = CastlingRights::NONE;
+
+            for c in castling_rights.chars() {
+                match c {
+                    // Shredder-FEN uses the files of the rooks instead. Only the
+                    // outer files correspond to the standard castles.
+                    'K' | 'H' => rights.add(Color::White, CastlingSide::KingSide),
+                    'Q' | 'A' => rights.add(Color::White, CastlingSide::QueenSide),
+                    'k' | 'h' => rights.add(Color::Black, CastlingSide::KingSide),
+                    'q' | 'a' => rights.add(Color::Black, CastlingSide::QueenSide),
+                    _ => return Err(FenError::BadCastling(castling_rights.to_owned())),
+                }
             }
+
+            board.set_castling_rights(rights);
+
+            Ok(())
         }
 
-        fn en_passant_pos(en_passant_pos: &str, board: &mut Board) -> Result<(), String> {
+        fn half_move_clock(
+            half_move_clock: Option<&&str>,
+            board: &mut Board,
+        ) -> Result<(), FenError> {
+            // The field is optional, since many (e.g. perft) FENs omit it.
+            board.half_move_clock = match half_move_clock {
+                None | Some(&"") => 0,
+                Some(clock) => clock
+                    .parse()
+                    .map_err(|_| FenError::BadClock(clock.to_string()))?,
+            };
+
+            Ok(())
+        }
+
+        fn full_move_number(
+            full_move_number: Option<&&str>,
+            board: &mut Board,
+        ) -> Result<(), FenError> {
+            // Same as the half move clock, the field is optional.
+            board.full_move_number = match full_move_number {
+                None | Some(&"") => 1,
+                Some(number) => number
+                    .parse()
+                    .map_err(|_| FenError::BadClock(number.to_string()))?,
+            };
+
+            Ok(())
+        }
+
+        fn en_passant_pos(en_passant_pos: &str, board: &mut Board) -> Result<(), FenError> {
             if en_passant_pos != "-" {
-                board.en_passant_target_idx = Some(Square::from_fen(en_passant_pos)?.into());
+                board.set_en_passant_square(Some(Square::from_fen(en_passant_pos)?));
             }
 
             Ok(())
@@ -247,6 +339,81 @@ impl Fen for Board {
     }
 }
 
+/// Parses an EPD (Extended Position Description) line, as used by test suites.
+///
+/// The first four fields are the same as in a FEN, the clocks are set to their
+/// defaults. They are followed by operations in the form of `opcode value;`, which
+/// are returned in a map. Quotes around the values are removed, other values (e.g.
+/// the SAN moves of `bm` and `am`) are returned unchanged.
+pub fn from_epd(epd: &str) -> Result<(Board, HashMap<String, String>), String> {
+    let mut rest = epd.trim();
+    let mut fields = Vec::new();
+
+    for _ in 0..4 {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+
+    if fields.iter().any(|field| field.is_empty()) {
+        return Err(format!("expected four position fields in '{}'", epd));
+    }
+
+    let board =
+        Board::from_fen(&format!("{} 0 1", fields.join(" "))).map_err(|err| err.to_string())?;
+
+    let mut operations = HashMap::new();
+    let mut operation = String::new();
+    let mut in_quotes = false;
+
+    for c in rest.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                operation.push(c);
+            }
+            ';' if !in_quotes => {
+                add_operation(&operation, &mut operations);
+                operation.clear();
+            }
+            _ => operation.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(format!("unterminated string in '{}'", epd));
+    }
+
+    if !operation.trim().is_empty() {
+        return Err(format!(
+            "operation '{}' is not terminated by ';'",
+            operation.trim()
+        ));
+    }
+
+    return Ok((board, operations));
+
+    fn add_operation(operation: &str, operations: &mut HashMap<String, String>) {
+        let operation = operation.trim();
+
+        if operation.is_empty() {
+            return;
+        }
+
+        let (opcode, value) = operation
+            .split_once(char::is_whitespace)
+            .unwrap_or((operation, ""));
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+
+        operations.insert(opcode.to_owned(), value.to_owned());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -257,7 +424,7 @@ mod tests {
 
     #[test]
     fn starting_formation() {
-        let truth = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0";
+        let truth = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
         let board = Board::new_with_standard_formation();
 
@@ -269,7 +436,7 @@ mod tests {
     fn not_starting_formation() {
         use Square::*;
 
-        let truth = "3B4/k6P/4N1p1/K7/1P3PBp/5P1r/R3P1Pp/8 w - - 0 0";
+        let truth = "3B4/k6P/4N1p1/K7/1P3PBp/5P1r/R3P1Pp/8 w - - 0 1";
 
         let mut board = Board::new_empty();
         board.set(Color::White, Piece::Bishop, D8);
@@ -295,7 +462,7 @@ mod tests {
 
     #[test]
     fn white_to_move() {
-        let truth = "8/8/8/8/8/8/8/8 w - - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w - - 0 1";
 
         let board = Board::new_empty();
 
@@ -305,10 +472,10 @@ mod tests {
 
     #[test]
     fn black_to_move() {
-        let truth = "8/8/8/8/8/8/8/8 b - - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 b - - 0 1";
 
         let mut board = Board::new_empty();
-        board.is_whites_turn = false;
+        board.set_side_to_move(Color::Black);
 
         assert_eq!(board.get_fen(), truth);
         assert_eq!(board, Board::from_fen(truth).unwrap());
@@ -316,31 +483,35 @@ mod tests {
 
     #[test]
     fn castle_none() {
-        let truth = "8/8/8/8/8/8/8/8 w - - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w - - 0 1";
 
         let board = Board::new_empty();
 
         assert_eq!(board.get_fen(), truth);
-        assert_eq!(board, Board::from_fen("8/8/8/8/8/8/8/8 w - - 0 0").unwrap());
+        assert_eq!(board, Board::from_fen("8/8/8/8/8/8/8/8 w - - 0 1").unwrap());
     }
 
     #[test]
     fn castle_white_queen_side() {
-        let truth = "8/8/8/8/8/8/8/8 w Q - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w Q - 0 1";
 
         let mut board = Board::new_empty();
-        board.can_white_castle_queen_side = true;
+        let mut rights = CastlingRights::NONE;
+        rights.add(Color::White, CastlingSide::QueenSide);
+        board.set_castling_rights(rights);
 
         assert_eq!(board.get_fen(), truth);
-        assert_eq!(board, Board::from_fen("8/8/8/8/8/8/8/8 w Q - 0 0").unwrap());
+        assert_eq!(board, Board::from_fen("8/8/8/8/8/8/8/8 w Q - 0 1").unwrap());
     }
 
     #[test]
     fn castle_white_king_side() {
-        let truth = "8/8/8/8/8/8/8/8 w K - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w K - 0 1";
 
         let mut board = Board::new_empty();
-        board.can_white_castle_king_side = true;
+        let mut rights = CastlingRights::NONE;
+        rights.add(Color::White, CastlingSide::KingSide);
+        board.set_castling_rights(rights);
 
         assert_eq!(board.get_fen(), truth);
         assert_eq!(board, Board::from_fen(truth).unwrap());
@@ -348,10 +519,12 @@ mod tests {
 
     #[test]
     fn castle_black_queen_side() {
-        let truth = "8/8/8/8/8/8/8/8 w q - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w q - 0 1";
 
         let mut board = Board::new_empty();
-        board.can_black_castle_queen_side = true;
+        let mut rights = CastlingRights::NONE;
+        rights.add(Color::Black, CastlingSide::QueenSide);
+        board.set_castling_rights(rights);
 
         assert_eq!(board.get_fen(), truth);
         assert_eq!(board, Board::from_fen(truth).unwrap());
@@ -359,10 +532,12 @@ mod tests {
 
     #[test]
     fn castle_black_king_side() {
-        let truth = "8/8/8/8/8/8/8/8 w k - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w k - 0 1";
 
         let mut board = Board::new_empty();
-        board.can_black_castle_king_side = true;
+        let mut rights = CastlingRights::NONE;
+        rights.add(Color::Black, CastlingSide::KingSide);
+        board.set_castling_rights(rights);
 
         assert_eq!(board.get_fen(), truth);
         assert_eq!(board, Board::from_fen(truth).unwrap());
@@ -370,13 +545,10 @@ mod tests {
 
     #[test]
     fn castle_all_sides() {
-        let truth = "8/8/8/8/8/8/8/8 w KQkq - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w KQkq - 0 1";
 
         let mut board = Board::new_empty();
-        board.can_white_castle_king_side = true;
-        board.can_white_castle_queen_side = true;
-        board.can_black_castle_king_side = true;
-        board.can_black_castle_queen_side = true;
+        board.set_castling_rights(CastlingRights::ALL);
 
         assert_eq!(board.get_fen(), truth);
         assert_eq!(board, Board::from_fen(truth).unwrap());
@@ -384,7 +556,7 @@ mod tests {
 
     #[test]
     fn en_passant_none() {
-        let truth = "8/8/8/8/8/8/8/8 w - - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w - - 0 1";
 
         let board = Board::new_empty();
 
@@ -394,10 +566,10 @@ mod tests {
 
     #[test]
     fn en_passant_e4() {
-        let truth = "8/8/8/8/8/8/8/8 w - e4 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w - e4 0 1";
 
         let mut board = Board::new_empty();
-        board.en_passant_target_idx = Some(Square::E4.into());
+        board.set_en_passant_square(Some(Square::E4));
 
         assert_eq!(board.get_fen(), truth);
         assert_eq!(board, Board::from_fen(truth).unwrap());
@@ -405,10 +577,10 @@ mod tests {
 
     #[test]
     fn en_passant_c5() {
-        let truth = "8/8/8/8/8/8/8/8 w - c5 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w - c5 0 1";
 
         let mut board = Board::new_empty();
-        board.en_passant_target_idx = Some(Square::C5.into());
+        board.set_en_passant_square(Some(Square::C5));
 
         assert_eq!(board.get_fen(), truth);
         assert_eq!(board, Board::from_fen(truth).unwrap());
@@ -416,25 +588,187 @@ mod tests {
 
     #[test]
     fn half_move_clock() {
-        let truth = "8/8/8/8/8/8/8/8 w - - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w - - 37 1";
 
-        // Currently the half move clock is not relevant in this engine, and thus
-        // always emitted as 0.
-        let board = Board::new_empty();
+        let mut board = Board::new_empty();
+        board.half_move_clock = 37;
 
         assert_eq!(board.get_fen(), truth);
         assert_eq!(board, Board::from_fen(truth).unwrap());
     }
 
+    #[test]
+    fn half_move_clock_missing() {
+        let board = Board::from_fen("8/8/8/8/8/8/8/8 w - -").unwrap();
+
+        assert_eq!(board.half_move_clock, 0);
+    }
+
     #[test]
     fn full_move_counter() {
-        let truth = "8/8/8/8/8/8/8/8 w - - 0 0";
+        let truth = "8/8/8/8/8/8/8/8 w - - 0 42";
 
-        // Currently the full move container is not relevant in this engine, and
-        // thus always emitted as 0.
-        let board = Board::new_empty();
+        let mut board = Board::new_empty();
+        board.full_move_number = 42;
 
         assert_eq!(board.get_fen(), truth);
         assert_eq!(board, Board::from_fen(truth).unwrap());
     }
+
+    #[test]
+    fn full_move_counter_missing() {
+        let board = Board::from_fen("8/8/8/8/8/8/8/8 w - -").unwrap();
+
+        assert_eq!(board.full_move_number, 1);
+    }
+
+    #[test]
+    fn move_counters_full_fen() {
+        let board =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2")
+                .unwrap();
+
+        assert_eq!(board.half_move_clock, 1);
+        assert_eq!(board.full_move_number, 2);
+    }
+
+    #[test]
+    fn move_counters_not_numeric() {
+        assert!(Board::from_fen("8/8/8/8/8/8/8/8 w - - x 1").is_err());
+        assert!(Board::from_fen("8/8/8/8/8/8/8/8 w - - 0 -1").is_err());
+    }
+
+    #[test]
+    fn extra_whitespace() {
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+        let board = Board::from_fen(fen).unwrap();
+
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1 ",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR  b KQkq  e3 0  1",
+            " \trnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        ] {
+            assert_eq!(Board::from_fen(fen), Ok(board.clone()), "{:?}", fen);
+        }
+    }
+
+    #[test]
+    fn error_variants() {
+        for (fen, err) in [
+            ("8/8/8/8/8/8/8/7x w - - 0 1", FenError::BadPieceChar('x')),
+            (
+                "8/8/8/8/8/8/8/8 x - - 0 1",
+                FenError::BadSideToMove("x".to_owned()),
+            ),
+            (
+                "8/8/8/8/8/8/8/8 w - e9 0 1",
+                FenError::BadSquare("e9".to_owned()),
+            ),
+            (
+                "8/8/8/8/8/8/8/8 w - - x 1",
+                FenError::BadClock("x".to_owned()),
+            ),
+            ("8/8/8/8/8/8/8/8 w", FenError::MissingField(2)),
+        ] {
+            assert_eq!(Board::from_fen(fen), Err(err), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn castling_rights() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        assert!(board
+            .castling_rights()
+            .has(Color::White, CastlingSide::KingSide));
+        assert!(board
+            .castling_rights()
+            .has(Color::White, CastlingSide::QueenSide));
+        assert!(board
+            .castling_rights()
+            .has(Color::Black, CastlingSide::KingSide));
+        assert!(board
+            .castling_rights()
+            .has(Color::Black, CastlingSide::QueenSide));
+
+        // Shredder-FEN
+        assert_eq!(
+            Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w HAh - 0 1").unwrap(),
+            Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQk - 0 1").unwrap()
+        );
+
+        for castling_rights in ["XY", "KQx", "--", "Kb"] {
+            let fen = format!("r3k2r/8/8/8/8/8/8/R3K2R w {} - 0 1", castling_rights);
+
+            assert!(
+                matches!(
+                    Board::from_fen(&fen),
+                    Err(FenError::BadCastling(_)) | Err(FenError::MissingField(_))
+                ),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn placement_invalid() {
+        for fen in [
+            "8/8/8 w - - 0 1",
+            "9/8/8/8/8/8/8/8 w - - 0 1",
+            "7/8/8/8/8/8/8/8 w - - 0 1",
+            "ppppppppp/8/8/8/8/8/8/8 w - - 0 1",
+            "rnbqkbnr/pppppppp p/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/ w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1",
+        ] {
+            assert!(
+                matches!(Board::from_fen(fen), Err(FenError::BadPlacement(_))),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn epd_operations() {
+        let (board, operations) = from_epd(
+            r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            board,
+            Board::from_fen("2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1").unwrap()
+        );
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations["bm"], "Qg6");
+        assert_eq!(operations["id"], "WAC.001");
+    }
+
+    #[test]
+    fn epd_quoted_semicolon_and_no_operations() {
+        let (_, operations) =
+            from_epd(r#"4k3/8/8/8/8/8/8/4K3 b - - am Kd8 Kf8; c0 "a; b";"#).unwrap();
+
+        assert_eq!(operations["am"], "Kd8 Kf8");
+        assert_eq!(operations["c0"], "a; b");
+
+        let (board, operations) = from_epd("4k3/8/8/8/8/8/8/4K3 b - -").unwrap();
+
+        assert_eq!(board.side_to_move(), Color::Black);
+        assert!(operations.is_empty());
+    }
+
+    #[test]
+    fn epd_invalid() {
+        for epd in [
+            "4k3/8/8/8/8/8/8/4K3 w -",
+            "4k3/8/8/8/8/8/8/4K3 x - - bm Kd1;",
+            "4k3/8/8/8/8/8/8/4K3 w - - bm Kd1",
+            r#"4k3/8/8/8/8/8/8/4K3 w - - id "unterminated;"#,
+        ] {
+            assert!(from_epd(epd).is_err(), "{}", epd);
+        }
+    }
 }
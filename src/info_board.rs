@@ -0,0 +1,146 @@
+use crate::{
+    board::{BoardPos, PieceInstance},
+    move_generator, Board, Square,
+};
+
+/// What should be displayed on a single square of an [`InfoBoard`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PosInfo {
+    /// An empty square a piece can move to.
+    Move,
+    /// An empty square.
+    None,
+    /// A square occupied by a piece.
+    Piece(PieceInstance),
+    /// A square occupied by a piece that can be captured.
+    PieceHit(PieceInstance),
+}
+
+/// A board enriched with information used for displaying it.
+///
+/// In contrast to [`Board`], this is not used for any game logic, but only
+/// describes what is shown on each square.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InfoBoard {
+    poses: [PosInfo; Board::SIZE],
+}
+
+impl InfoBoard {
+    pub fn get(&self, pos: impl BoardPos) -> &PosInfo {
+        &self.poses[pos.into()]
+    }
+
+    pub fn new_empty() -> Self {
+        Self {
+            poses: std::array::from_fn(|_| PosInfo::None),
+        }
+    }
+
+    pub fn poses(&self) -> &[PosInfo; Board::SIZE] {
+        &self.poses
+    }
+
+    pub fn set(&mut self, pos: impl BoardPos, info: PosInfo) {
+        self.poses[pos.into()] = info;
+    }
+
+    /// Creates an info board with the legal moves of the piece on `src` marked.
+    ///
+    /// Empty destinations are marked as [`PosInfo::Move`], occupied ones as
+    /// [`PosInfo::PieceHit`]. En passant captures land on an empty square, and are
+    /// therefore marked as a move.
+    pub fn with_moves_from(board: &Board, src: Square) -> Self {
+        let mut info_board = Self::from(board);
+
+        for mv in move_generator::moves_from(board, src) {
+            let info = match board.get(mv.dst()) {
+                Some(ins) => PosInfo::PieceHit(ins),
+                None => PosInfo::Move,
+            };
+
+            info_board.set(mv.dst(), info);
+        }
+
+        info_board
+    }
+}
+
+impl From<&Board> for InfoBoard {
+    fn from(board: &Board) -> Self {
+        let mut info_board = Self::new_empty();
+
+        for (sq, ins) in board.pieces() {
+            info_board.set(sq, PosInfo::Piece(ins));
+        }
+
+        info_board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::Fen, Color, Piece};
+
+    use super::*;
+
+    #[test]
+    fn from_standard_formation() {
+        let info_board = InfoBoard::from(&Board::new_with_standard_formation());
+        let count = |color: Color| {
+            info_board
+                .poses()
+                .iter()
+                .filter(|info| matches!(info, PosInfo::Piece(ins) if ins.color == color))
+                .count()
+        };
+
+        assert_eq!(count(Color::Black), 16);
+        assert_eq!(count(Color::White), 16);
+        assert_eq!(
+            info_board.get(Square::E1),
+            &PosInfo::Piece(PieceInstance::new(Color::White, Piece::King))
+        );
+        assert_eq!(info_board.get(Square::E4), &PosInfo::None);
+    }
+
+    #[test]
+    fn with_moves_from_knight() {
+        let board = Board::from_fen("4k3/8/8/3p4/8/4N3/8/4K3 w - - 0 1").unwrap();
+        let info_board = InfoBoard::with_moves_from(&board, Square::E3);
+
+        for sq in [
+            Square::C2,
+            Square::C4,
+            Square::D1,
+            Square::F1,
+            Square::F5,
+            Square::G2,
+            Square::G4,
+        ] {
+            assert_eq!(info_board.get(sq), &PosInfo::Move, "{}", sq);
+        }
+
+        assert_eq!(
+            info_board.get(Square::D5),
+            &PosInfo::PieceHit(PieceInstance::new(Color::Black, Piece::Pawn))
+        );
+        assert_eq!(
+            info_board
+                .poses()
+                .iter()
+                .filter(|info| matches!(info, PosInfo::Move | PosInfo::PieceHit(_)))
+                .count(),
+            8
+        );
+    }
+
+    #[test]
+    fn with_moves_from_empty_square() {
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(
+            InfoBoard::with_moves_from(&board, Square::E4),
+            InfoBoard::from(&board)
+        );
+    }
+}
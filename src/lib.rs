@@ -1,21 +1,34 @@
 pub mod bit_board;
 pub mod board;
+pub mod castling_rights;
+pub mod display_info_board;
 pub mod evaluation;
 pub mod fen;
+pub mod info_board;
 pub mod magic_bit_board;
 pub mod move_generator;
+pub mod pgn;
 pub mod piece;
+pub mod search;
 pub mod square;
 pub mod type_alias_default;
+pub mod zobrist;
 
-#[cfg(test)]
-mod testing_utils;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing_utils;
+
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
 
 pub use board::Board;
 pub use piece::Piece;
 pub use square::Square;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     Black,
     White,
@@ -28,4 +41,111 @@ impl Color {
             Color::White => Color::Black,
         }
     }
+
+    /// The character used for the side to move in FEN strings (`'w'` or `'b'`).
+    pub const fn to_fen_char(self) -> char {
+        match self {
+            Color::Black => 'b',
+            Color::White => 'w',
+        }
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Color::Black => write!(f, "black"),
+            Color::White => write!(f, "white"),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Parses `"w"`, `"white"`, `"b"`, or `"black"` (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "b" | "black" => Ok(Color::Black),
+            "w" | "white" => Ok(Color::White),
+            _ => Err(format!(
+                "cannot convert from '{}' to color, expected 'w', 'white', 'b', or 'black'",
+                s
+            )),
+        }
+    }
+}
+
+/// A value for each color, which can only be indexed by a [`Color`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByColor<T>([T; 2]);
+
+impl<T> ByColor<T> {
+    pub const fn new(black: T, white: T) -> Self {
+        Self([black, white])
+    }
+
+    /// Iterates over the values, together with their color (black first).
+    pub fn iter(&self) -> impl Iterator<Item = (Color, &T)> {
+        [Color::Black, Color::White].into_iter().zip(&self.0)
+    }
+}
+
+impl<T> Index<Color> for ByColor<T> {
+    type Output = T;
+
+    fn index(&self, index: Color) -> &Self::Output {
+        &self.0[index as usize]
+    }
+}
+
+impl<T> IndexMut<Color> for ByColor<T> {
+    fn index_mut(&mut self, index: Color) -> &mut Self::Output {
+        &mut self.0[index as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn by_color_iter() {
+        let mut by_color = ByColor::new(1, 2);
+        by_color[Color::White] += 1;
+
+        assert_eq!(
+            by_color.iter().collect::<Vec<_>>(),
+            [(Color::Black, &1), (Color::White, &3)]
+        );
+    }
+
+    #[test]
+    fn color_from_str() {
+        for (s, color) in [
+            ("b", Color::Black),
+            ("B", Color::Black),
+            ("black", Color::Black),
+            ("Black", Color::Black),
+            ("w", Color::White),
+            ("W", Color::White),
+            ("white", Color::White),
+            ("WHITE", Color::White),
+        ] {
+            assert_eq!(s.parse(), Ok(color), "{}", s);
+        }
+    }
+
+    #[test]
+    fn color_from_str_invalid() {
+        assert!("red".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn color_display_and_fen_char() {
+        assert_eq!(Color::Black.to_string(), "black");
+        assert_eq!(Color::White.to_string(), "white");
+        assert_eq!(Color::Black.to_fen_char(), 'b');
+        assert_eq!(Color::White.to_fen_char(), 'w');
+    }
 }
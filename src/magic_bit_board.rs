@@ -27,7 +27,31 @@ use crate::{
 ///
 /// Abstracts away all the table lookups maths. Read the module-level documentation
 /// for more information.
+///
+/// With the `bmi2` feature, the lookup is done using [`pext`] instead, if the CPU
+/// supports it.
 pub fn get_bishop_attacks_for(idx: usize, blockers: u64) -> u64 {
+    #[cfg(all(feature = "bmi2", target_arch = "x86_64"))]
+    if *pext::IS_AVAILABLE {
+        // SAFETY: BMI2 support was checked just now.
+        return unsafe { pext::get_bishop_attacks_for(idx, blockers) };
+    }
+
+    get_magic_bishop_attacks_for(idx, blockers)
+}
+
+/// Same as [`get_bishop_attacks_for`], but for rooks.
+pub fn get_rook_attacks_for(idx: usize, blockers: u64) -> u64 {
+    #[cfg(all(feature = "bmi2", target_arch = "x86_64"))]
+    if *pext::IS_AVAILABLE {
+        // SAFETY: BMI2 support was checked just now.
+        return unsafe { pext::get_rook_attacks_for(idx, blockers) };
+    }
+
+    get_magic_rook_attacks_for(idx, blockers)
+}
+
+fn get_magic_bishop_attacks_for(idx: usize, blockers: u64) -> u64 {
     let magic_index = magic_index_of(
         BISHOP_MAGIC_NUMBERS[idx],
         blockers,
@@ -35,11 +59,10 @@ pub fn get_bishop_attacks_for(idx: usize, blockers: u64) -> u64 {
         NUMBER_OF_RELEVANT_BISHOP_MOVES_PER_SQUARE[idx] as usize,
     );
 
-    ALL_POSSIBLE_BISHOP_ATTACKS[magic_index][idx]
+    ALL_POSSIBLE_BISHOP_ATTACKS[idx][magic_index]
 }
 
-/// Same as [`get_bishop_attacks_for`], but for rooks.
-pub fn get_rook_attacks_for(idx: usize, blockers: u64) -> u64 {
+fn get_magic_rook_attacks_for(idx: usize, blockers: u64) -> u64 {
     let magic_index = magic_index_of(
         ROOK_MAGIC_NUMBERS[idx],
         blockers,
@@ -47,7 +70,7 @@ pub fn get_rook_attacks_for(idx: usize, blockers: u64) -> u64 {
         NUMBER_OF_RELEVANT_ROOK_MOVES_PER_SQUARE[idx] as usize,
     );
 
-    ALL_POSSIBLE_ROOK_ATTACKS[magic_index][idx]
+    ALL_POSSIBLE_ROOK_ATTACKS[idx][magic_index]
 }
 
 #[cfg(test)]
@@ -73,6 +96,129 @@ mod tests {
             assert_eq!(truth, lookup_result);
         }
     }
+
+    #[test]
+    fn compare_to_slow_to_generate_source_of_truth_with_random_blockers() {
+        bb::seed_random(1082485);
+
+        for _ in 0..100 {
+            // Sparse blockers, as dense ones would mostly stop the rays right away.
+            let blockers = random_u64(bb::seeded_random_u32) & random_u64(bb::seeded_random_u32);
+
+            for i in 0..Board::SIZE {
+                assert_eq!(
+                    get_bishop_attacks_for(i, blockers),
+                    piece::calculate_bishop_attacks_for(i, blockers)
+                );
+                assert_eq!(
+                    get_rook_attacks_for(i, blockers),
+                    piece::calculate_rook_attacks_for(i, blockers)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn attack_tables_are_sized_per_square() {
+        for i in 0..Board::SIZE {
+            assert_eq!(
+                ALL_POSSIBLE_BISHOP_ATTACKS[i].len(),
+                1 << NUMBER_OF_RELEVANT_BISHOP_MOVES_PER_SQUARE[i]
+            );
+            assert_eq!(
+                ALL_POSSIBLE_ROOK_ATTACKS[i].len(),
+                1 << NUMBER_OF_RELEVANT_ROOK_MOVES_PER_SQUARE[i]
+            );
+        }
+    }
+
+    #[test]
+    fn random_u32_is_not_shared_between_threads() {
+        let sequences = (0..4)
+            .map(|_| std::thread::spawn(|| (0..1000).map(|_| bb::random_u32()).collect::<Vec<_>>()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        for (i, sequence) in sequences.iter().enumerate() {
+            for other in &sequences[i + 1..] {
+                assert_ne!(sequence, other);
+                assert!(sequence.iter().all(|n| !other.contains(n)));
+            }
+        }
+    }
+
+    #[cfg(all(feature = "bmi2", target_arch = "x86_64"))]
+    #[test]
+    fn pext_compare_to_magic() {
+        if !*pext::IS_AVAILABLE {
+            return;
+        }
+
+        bb::seed_random(1082485);
+
+        for _ in 0..100 {
+            let blockers = random_u64(bb::seeded_random_u32) & random_u64(bb::seeded_random_u32);
+
+            for i in 0..Board::SIZE {
+                // SAFETY: BMI2 support was checked above.
+                unsafe {
+                    assert_eq!(
+                        pext::get_bishop_attacks_for(i, blockers),
+                        get_magic_bishop_attacks_for(i, blockers)
+                    );
+                    assert_eq!(
+                        pext::get_rook_attacks_for(i, blockers),
+                        get_magic_rook_attacks_for(i, blockers)
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "gen-magics")]
+    #[test]
+    fn regenerated_magics_compare_to_slow_to_generate_source_of_truth() {
+        let (bishop_magics, rook_magics) = regenerate_magics(1082485);
+
+        assert_eq!(regenerate_magics(1082485), (bishop_magics, rook_magics));
+
+        for (relevant_moves, number_of_relevant_moves, magics, calculate_attacks_for) in [
+            (
+                &*RELEVANT_BISHOP_MOVES_PER_SQUARE,
+                &NUMBER_OF_RELEVANT_BISHOP_MOVES_PER_SQUARE,
+                &bishop_magics,
+                piece::calculate_bishop_attacks_for as fn(usize, u64) -> u64,
+            ),
+            (
+                &*RELEVANT_ROOK_MOVES_PER_SQUARE,
+                &NUMBER_OF_RELEVANT_ROOK_MOVES_PER_SQUARE,
+                &rook_magics,
+                piece::calculate_rook_attacks_for,
+            ),
+        ] {
+            let attacks = generate_all_possible_attacks_for(
+                relevant_moves,
+                number_of_relevant_moves,
+                magics,
+                calculate_attacks_for,
+            );
+
+            for i in 0..Board::SIZE {
+                for blockers in [0, 0x0000_0018_1800_0000, 0x00ff_0000_0000_ff00] {
+                    let magic_index = magic_index_of(
+                        magics[i],
+                        blockers,
+                        relevant_moves[i],
+                        number_of_relevant_moves[i] as usize,
+                    );
+
+                    assert_eq!(attacks[i][magic_index], calculate_attacks_for(i, blockers));
+                }
+            }
+        }
+    }
 }
 
 /// Generated using [`generate_bishop_magic_numbers`].
@@ -257,10 +403,16 @@ const NUMBER_OF_RELEVANT_ROOK_MOVES_PER_SQUARE: [u64; 64] = [
     12, 11, 11, 11, 11, 11, 11, 12,
 ];
 
-static ALL_POSSIBLE_BISHOP_ATTACKS: Lazy<Box<[U64PerSquare; 4096]>> =
+/// The attacks for every square, indexed by the square, and then by the magic index
+/// (see [`magic_index_of`]).
+///
+/// The table of each square only holds as many entries as there are occupancy
+/// variants on it (see [`number_of_occupancy_variants`]).
+type AttackTable = [Box<[u64]>; Board::SIZE];
+
+static ALL_POSSIBLE_BISHOP_ATTACKS: Lazy<AttackTable> =
     Lazy::new(generate_all_possible_bishop_attacks);
-static ALL_POSSIBLE_ROOK_ATTACKS: Lazy<Box<[U64PerSquare; 4096]>> =
-    Lazy::new(generate_all_possible_rook_attacks);
+static ALL_POSSIBLE_ROOK_ATTACKS: Lazy<AttackTable> = Lazy::new(generate_all_possible_rook_attacks);
 
 /// Read the module-level documentation for more information.
 ///
@@ -268,22 +420,45 @@ static ALL_POSSIBLE_ROOK_ATTACKS: Lazy<Box<[U64PerSquare; 4096]>> =
 /// [`BISHOP_MAGIC_NUMBERS`]).
 #[allow(dead_code)]
 fn generate_bishop_magic_numbers() -> U64PerSquare {
-    let mut numbers = U64PerSquare::default();
-
-    for i in 0..Board::SIZE {
-        numbers[i] = generate_magic_number_for(i, Piece::Bishop);
-    }
-
-    numbers
+    generate_magic_numbers_for(Piece::Bishop, bb::random_u32)
 }
 
 /// Same as [`generate_bishop_magic_numbers`], but for rooks.
 #[allow(dead_code)]
 fn generate_rook_magic_numbers() -> U64PerSquare {
+    generate_magic_numbers_for(Piece::Rook, bb::random_u32)
+}
+
+/// Regenerates the bishop and rook magic numbers (in that order) from `seed`.
+///
+/// In contrast to [`generate_bishop_magic_numbers`] and
+/// [`generate_rook_magic_numbers`], the random numbers are drawn from a seeded,
+/// thread-local generator, so the same seed always results in the same magic
+/// numbers. This can be used to reproducibly regenerate the baked-in
+/// [`BISHOP_MAGIC_NUMBERS`] and [`ROOK_MAGIC_NUMBERS`].
+///
+/// # Panics
+///
+/// If `seed` is `0`, as the generator would only ever produce zeros.
+#[cfg(feature = "gen-magics")]
+pub fn regenerate_magics(seed: u32) -> (U64PerSquare, U64PerSquare) {
+    assert_ne!(seed, 0, "the seed must not be zero");
+
+    bb::seed_random(seed);
+
+    (
+        generate_magic_numbers_for(Piece::Bishop, bb::seeded_random_u32),
+        generate_magic_numbers_for(Piece::Rook, bb::seeded_random_u32),
+    )
+}
+
+#[allow(dead_code)]
+#[allow(clippy::needless_range_loop)]
+fn generate_magic_numbers_for(piece: Piece, random_u32: fn() -> u32) -> U64PerSquare {
     let mut numbers = U64PerSquare::default();
 
     for i in 0..Board::SIZE {
-        numbers[i] = generate_magic_number_for(i, Piece::Rook);
+        numbers[i] = generate_magic_number_for(i, piece, random_u32);
     }
 
     numbers
@@ -313,6 +488,7 @@ fn generate_rook_magic_numbers() -> U64PerSquare {
 ///
 ///     a b c d e f g h
 /// ```
+#[allow(clippy::needless_range_loop)]
 fn generate_relevant_bishop_moves_per_square() -> U64PerSquare {
     let mut moves = U64PerSquare::default();
 
@@ -328,16 +504,16 @@ fn generate_relevant_bishop_moves_per_square() -> U64PerSquare {
         let to_no_we = min(file, rank);
 
         for iter in 1..to_no_ea {
-            moves[i] |= board >> bit_board::NO_EA * iter;
+            moves[i] |= board >> (bit_board::NO_EA * iter);
         }
         for iter in 1..to_so_ea {
-            moves[i] |= board << bit_board::SO_EA * iter;
+            moves[i] |= board << (bit_board::SO_EA * iter);
         }
         for iter in 1..to_so_we {
-            moves[i] |= board << bit_board::SO_WE * iter;
+            moves[i] |= board << (bit_board::SO_WE * iter);
         }
         for iter in 1..to_no_we {
-            moves[i] |= board >> bit_board::NO_WE * iter;
+            moves[i] |= board >> (bit_board::NO_WE * iter);
         }
     }
 
@@ -345,6 +521,7 @@ fn generate_relevant_bishop_moves_per_square() -> U64PerSquare {
 }
 
 /// Same as [`generate_relevant_bishop_moves_per_square`], but for rooks.
+#[allow(clippy::needless_range_loop)]
 fn generate_relevant_rook_moves_per_square() -> U64PerSquare {
     let mut moves = U64PerSquare::default();
 
@@ -360,23 +537,23 @@ fn generate_relevant_rook_moves_per_square() -> U64PerSquare {
         let to_west = file;
 
         for iter in 1..to_north {
-            moves[i] |= board >> bit_board::NORTH * iter;
+            moves[i] |= board >> (bit_board::NORTH * iter);
         }
         for iter in 1..to_east {
-            moves[i] |= board << bit_board::EAST * iter;
+            moves[i] |= board << (bit_board::EAST * iter);
         }
         for iter in 1..to_south {
-            moves[i] |= board << bit_board::SOUTH * iter;
+            moves[i] |= board << (bit_board::SOUTH * iter);
         }
         for iter in 1..to_west {
-            moves[i] |= board >> bit_board::WEST * iter;
+            moves[i] |= board >> (bit_board::WEST * iter);
         }
     }
 
     moves
 }
 
-fn generate_all_possible_bishop_attacks() -> Box<[U64PerSquare; 4096]> {
+fn generate_all_possible_bishop_attacks() -> AttackTable {
     generate_all_possible_attacks_for(
         &RELEVANT_BISHOP_MOVES_PER_SQUARE,
         &NUMBER_OF_RELEVANT_BISHOP_MOVES_PER_SQUARE,
@@ -385,7 +562,7 @@ fn generate_all_possible_bishop_attacks() -> Box<[U64PerSquare; 4096]> {
     )
 }
 
-fn generate_all_possible_rook_attacks() -> Box<[U64PerSquare; 4096]> {
+fn generate_all_possible_rook_attacks() -> AttackTable {
     generate_all_possible_attacks_for(
         &RELEVANT_ROOK_MOVES_PER_SQUARE,
         &NUMBER_OF_RELEVANT_ROOK_MOVES_PER_SQUARE,
@@ -399,17 +576,14 @@ fn generate_all_possible_attacks_for(
     number_of_all_relevant_moves: &U64PerSquare,
     magic_numbers: &U64PerSquare,
     calculate_attacks_for: fn(usize, u64) -> u64,
-) -> Box<[U64PerSquare; 4096]> {
-    let mut all_attacks: Box<[U64PerSquare; 4096]> = vec![U64PerSquare::default(); 4096]
-        .into_boxed_slice()
-        .try_into()
-        .unwrap();
-
-    for i in 0..Board::SIZE {
+) -> AttackTable {
+    std::array::from_fn(|i| {
         let relevant_moves = all_relevant_moves[i];
         let number_of_relevant_moves = number_of_all_relevant_moves[i] as usize;
+        let number_of_variants = number_of_occupancy_variants(number_of_relevant_moves);
+        let mut attacks = vec![0; number_of_variants].into_boxed_slice();
 
-        for occupancy_idx in 0..number_of_occupancy_variants(number_of_relevant_moves) {
+        for occupancy_idx in 0..number_of_variants {
             let occupancy_variant =
                 bb::move_occupancy_variant(occupancy_idx, number_of_relevant_moves, relevant_moves);
             let magic_index = magic_index_of(
@@ -419,16 +593,14 @@ fn generate_all_possible_attacks_for(
                 number_of_relevant_moves,
             );
 
-            all_attacks[magic_index][i] = calculate_attacks_for(i, occupancy_variant);
+            attacks[magic_index] = calculate_attacks_for(i, occupancy_variant);
         }
-    }
 
-    all_attacks
+        attacks
+    })
 }
 
-fn generate_magic_number_for(idx: usize, piece: Piece) -> u64 {
-    let idx = idx.into();
-
+fn generate_magic_number_for(idx: usize, piece: Piece, random_u32: fn() -> u32) -> u64 {
     let (relevant_moves, number_of_relevant_moves, get_attacks_for): (
         u64,
         usize,
@@ -466,7 +638,7 @@ fn generate_magic_number_for(idx: usize, piece: Piece) -> u64 {
 
     const GENERATION_TRIES: u64 = 10000000000000;
     'generation_try: for _ in 0..GENERATION_TRIES {
-        let magic_number = get_magic_number_candidate();
+        let magic_number = get_magic_number_candidate(random_u32);
 
         // TODO: figure out what the point of this is
         if bit_board::count_set_bits(
@@ -506,11 +678,13 @@ fn generate_magic_number_for(idx: usize, piece: Piece) -> u64 {
 /// Takes in all the squares a move could reach, and calculates the amount of variants
 /// of pieces on those squares.
 ///
+/// Every relevant square can either be occupied or empty, so there are
+/// `2^number_of_relevant_moves` variants. This is also the number of distinct magic
+/// indexes on a square (see [`magic_index_of`]), and therefore the size of its
+/// attack table.
+///
 /// TODO: add example.
 fn number_of_occupancy_variants(number_of_relevant_moves: usize) -> usize {
-    // TODO: Reduce this number.
-    // Currently this results in a vast overestimate of this number (I think).
-    // Maybe there is a more sensible way to do this. Hardcode a value maybe?
     1 << number_of_relevant_moves
 }
 
@@ -519,7 +693,7 @@ fn number_of_occupancy_variants(number_of_relevant_moves: usize) -> usize {
 /// # Arguments
 ///
 /// * `magic_number` - "magic" (**generated** random) number, used to differentiate similar
-///    `occupancies`, see [`find_magic_number`] to checkout the generation.
+///   `occupancies`, see [`find_magic_number`] to checkout the generation.
 /// * `occupancies` - what squares are occupied
 /// * `number_of_occupied_sports` - used to further differentiate indexes
 fn magic_index_of(
@@ -529,23 +703,113 @@ fn magic_index_of(
     number_of_relevant_moves: usize,
 ) -> usize {
     occupancies &= relevant_move_mask;
-    occupancies.wrapping_mul(magic_number) as usize >> 64 - number_of_relevant_moves
+    occupancies.wrapping_mul(magic_number) as usize >> (64 - number_of_relevant_moves)
 }
 
 /// Generate a number that has a low amount of bits set to one.
-fn get_magic_number_candidate() -> u64 {
-    random_u64() & random_u64() & random_u64()
+fn get_magic_number_candidate(random_u32: fn() -> u32) -> u64 {
+    random_u64(random_u32) & random_u64(random_u32) & random_u64(random_u32)
 }
 
-fn random_u64() -> u64 {
-    let n1 = bb::random_u32() as u64 & 0xFFFF;
-    let n2 = (bb::random_u32() as u64 & 0xFFFF) << 16;
-    let n3 = (bb::random_u32() as u64 & 0xFFFF) << 32;
-    let n4 = (bb::random_u32() as u64 & 0xFFFF) << 48;
+fn random_u64(random_u32: fn() -> u32) -> u64 {
+    let n1 = random_u32() as u64 & 0xFFFF;
+    let n2 = (random_u32() as u64 & 0xFFFF) << 16;
+    let n3 = (random_u32() as u64 & 0xFFFF) << 32;
+    let n4 = (random_u32() as u64 & 0xFFFF) << 48;
 
     n1 | n2 | n3 | n4
 }
 
+/// Slider lookups using the BMI2 `pext` instruction.
+///
+/// `pext` extracts the blockers on the relevant squares into the low bits of the
+/// result, which makes it a perfect index into the attack table, without
+/// needing any magic numbers.
+#[cfg(all(feature = "bmi2", target_arch = "x86_64"))]
+mod pext {
+    use super::*;
+
+    /// Whether the CPU supports BMI2. Checked once, at the first lookup.
+    pub static IS_AVAILABLE: Lazy<bool> = Lazy::new(|| is_x86_feature_detected!("bmi2"));
+
+    static ALL_POSSIBLE_BISHOP_ATTACKS: Lazy<AttackTable> = Lazy::new(|| {
+        generate_all_possible_attacks_for(
+            &RELEVANT_BISHOP_MOVES_PER_SQUARE,
+            &NUMBER_OF_RELEVANT_BISHOP_MOVES_PER_SQUARE,
+            piece::calculate_bishop_attacks_for,
+        )
+    });
+    static ALL_POSSIBLE_ROOK_ATTACKS: Lazy<AttackTable> = Lazy::new(|| {
+        generate_all_possible_attacks_for(
+            &RELEVANT_ROOK_MOVES_PER_SQUARE,
+            &NUMBER_OF_RELEVANT_ROOK_MOVES_PER_SQUARE,
+            piece::calculate_rook_attacks_for,
+        )
+    });
+
+    /// Same as [`super::get_bishop_attacks_for`], but using `pext`.
+    ///
+    /// # Safety
+    ///
+    /// The CPU must support BMI2, see [`IS_AVAILABLE`].
+    pub unsafe fn get_bishop_attacks_for(idx: usize, blockers: u64) -> u64 {
+        // SAFETY: Upheld by the caller.
+        let i = unsafe { index_of(blockers, RELEVANT_BISHOP_MOVES_PER_SQUARE[idx]) };
+
+        ALL_POSSIBLE_BISHOP_ATTACKS[idx][i]
+    }
+
+    /// Same as [`get_bishop_attacks_for`], but for rooks.
+    ///
+    /// # Safety
+    ///
+    /// The CPU must support BMI2, see [`IS_AVAILABLE`].
+    pub unsafe fn get_rook_attacks_for(idx: usize, blockers: u64) -> u64 {
+        // SAFETY: Upheld by the caller.
+        let i = unsafe { index_of(blockers, RELEVANT_ROOK_MOVES_PER_SQUARE[idx]) };
+
+        ALL_POSSIBLE_ROOK_ATTACKS[idx][i]
+    }
+
+    fn generate_all_possible_attacks_for(
+        all_relevant_moves: &U64PerSquare,
+        number_of_all_relevant_moves: &U64PerSquare,
+        calculate_attacks_for: fn(usize, u64) -> u64,
+    ) -> AttackTable {
+        std::array::from_fn(|i| {
+            let relevant_moves = all_relevant_moves[i];
+            let number_of_relevant_moves = number_of_all_relevant_moves[i] as usize;
+
+            // The occupancy variants place the bits of their index on the relevant
+            // squares in ascending order, which is exactly what `pext` undoes.
+            (0..number_of_occupancy_variants(number_of_relevant_moves))
+                .map(|occupancy_idx| {
+                    let occupancy_variant = bb::move_occupancy_variant(
+                        occupancy_idx,
+                        number_of_relevant_moves,
+                        relevant_moves,
+                    );
+
+                    calculate_attacks_for(i, occupancy_variant)
+                })
+                .collect()
+        })
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support BMI2.
+    unsafe fn index_of(blockers: u64, relevant_move_mask: u64) -> usize {
+        // SAFETY: Upheld by the caller.
+        unsafe { pext_u64(blockers, relevant_move_mask) as usize }
+    }
+
+    #[target_feature(enable = "bmi2")]
+    fn pext_u64(a: u64, mask: u64) -> u64 {
+        core::arch::x86_64::_pext_u64(a, mask)
+    }
+}
+
 /// Code that I'm not sure what it does, or why it's used.
 ///
 /// 'bb' = Black Box.
@@ -553,7 +817,14 @@ fn random_u64() -> u64 {
 /// The goal is to have no code here in the future.
 mod bb {
     use super::*;
-    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::{
+        cell::Cell,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    thread_local! {
+        static SEEDED_STATE: Cell<u32> = const { Cell::new(1082485) };
+    }
 
     /// Generates all the possible variants of move occupancy bases on an index.
     ///
@@ -581,19 +852,47 @@ mod bb {
 
     /// Generates a pseudo random number.
     ///
+    /// The state is shared between all threads, and advanced atomically, so
+    /// concurrent callers never receive the same number.
+    ///
     /// Code from:
     /// https://youtu.be/JjFYmkUhLN4?list=PLmN0neTso3Jxh8ZIylk74JpwfiWNI76Cs&t=476
     pub fn random_u32() -> u32 {
         static STATE: AtomicU32 = AtomicU32::new(1082485);
 
-        let mut local_state = STATE.load(Ordering::Relaxed);
+        let previous_state = STATE
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |state| {
+                Some(xorshift(state))
+            })
+            .unwrap();
 
-        local_state ^= local_state << 13;
-        local_state ^= local_state >> 17;
-        local_state ^= local_state << 5;
+        xorshift(previous_state)
+    }
+
+    /// Sets the state of [`seeded_random_u32`] for the current thread.
+    #[allow(dead_code)]
+    pub fn seed_random(seed: u32) {
+        SEEDED_STATE.with(|state| state.set(seed));
+    }
+
+    /// Same as [`random_u32`], but using the thread-local state set by
+    /// [`seed_random`], so the generated sequence is reproducible.
+    #[allow(dead_code)]
+    pub fn seeded_random_u32() -> u32 {
+        SEEDED_STATE.with(|state| {
+            let new_state = xorshift(state.get());
+
+            state.set(new_state);
+
+            new_state
+        })
+    }
 
-        STATE.store(local_state, Ordering::Relaxed);
+    fn xorshift(mut state: u32) -> u32 {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
 
-        local_state
+        state
     }
 }
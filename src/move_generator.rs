@@ -1,8 +1,13 @@
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    thread,
+};
 
 use crate::{
     bit_board::{self, SetBitsIter, NORTH, SOUTH},
     board::BoardPos,
+    castling_rights::CastlingSide,
+    fen::FEN_SQUARE_SYMBOL_LOOKUP,
     piece::{self},
     Board,
     Color::{self, *},
@@ -12,41 +17,684 @@ use crate::{
 use Piece::*;
 use Square::*;
 
-// TODO: currently semi-legal moves (moves that put the king in check) are possible,
-// and not filtered out anywhere.
-
+/// Generates all pseudo-legal moves for the side to move.
+///
+/// Pseudo-legal moves may leave the own king in check. They are cheaper to
+/// generate, but have to be filtered by the caller (e.g. by checking the return
+/// value of [`Board::do_move`]). Use [`legal_moves`] to only get legal moves.
 pub fn all_moves(board: &Board) -> Vec<Move> {
+    let mut moves = Vec::new();
+
+    fill_moves(board, &mut moves);
+
+    moves
+}
+
+/// Same as [`all_moves`], but writes the moves into the given buffer.
+///
+/// The buffer is cleared first. Reusing the same buffer (e.g. one per search
+/// depth) avoids allocating a new vector for every position.
+pub fn fill_moves(board: &Board, out: &mut Vec<Move>) {
+    out.clear();
+
+    for piece in Piece::all() {
+        add_moves_of(board, piece, out);
+    }
+}
+
+/// Generates all pseudo-legal captures for the side to move.
+///
+/// Only moves landing on a square occupied by an opponent piece are generated,
+/// including capturing promotions (to every piece) and en passant captures. Like
+/// [`all_moves`], the moves may leave the own king in check.
+pub fn capture_moves(board: &Board) -> Vec<Move> {
+    let all_occ = board.all_occupancies();
+    let fren_color = board.side_to_move();
+    let opp_occ = board.occupied_by(fren_color.opposing());
+    // The helpers exclude the passed occupancies from the destinations, so by
+    // excluding everything but the opponent pieces, only captures remain.
+    let non_opp_occ = !opp_occ;
+
+    let mut moves = Vec::new();
+
+    add_bishop_moves(board, fren_color, all_occ, non_opp_occ, &mut moves);
+    add_king_moves_normal(board, fren_color, non_opp_occ, &mut moves);
+    add_knight_moves(
+        board.knights[fren_color],
+        non_opp_occ,
+        fren_color,
+        &mut moves,
+    );
+    add_pawn_captures(
+        board,
+        board.pawns[fren_color],
+        opp_occ,
+        fren_color,
+        &mut moves,
+    );
+    add_queen_moves(board, fren_color, all_occ, non_opp_occ, &mut moves);
+    add_rook_moves(board, fren_color, all_occ, non_opp_occ, &mut moves);
+
+    mark_captures(&mut moves, opp_occ);
+
+    moves
+}
+
+/// Generates all legal moves for the side to move.
+///
+/// Every pseudo-legal move (see [`all_moves`]) is executed on a copy of the board,
+/// and discarded if it leaves the own king attacked. Since the move is fully
+/// executed, this also covers the en passant edge case, where removing the
+/// captured pawn exposes the king along the rank.
+///
+/// When in check, only the candidates of [`evasion_moves`] are tried.
+pub fn legal_moves(board: &Board) -> Vec<Move> {
+    filter_legal(board).collect()
+}
+
+/// Same as `legal_moves(board).len()`, but without collecting the legal moves.
+///
+/// Used for bulk counting in [`perft`], where only the number of moves of the
+/// last ply is of interest.
+pub fn count_legal_moves(board: &Board) -> usize {
+    filter_legal(board).count()
+}
+
+/// Yields the legal moves for [`legal_moves`] and [`count_legal_moves`].
+///
+/// The board is cloned once, and every candidate is made and unmade on that copy,
+/// instead of cloning the board for every move.
+fn filter_legal(board: &Board) -> impl Iterator<Item = Move> {
+    let moves = match board.is_in_check() {
+        true => evasion_moves(board),
+        false => all_moves(board),
+    };
+    let mut board = board.clone();
+
+    moves.into_iter().filter(move |mv| {
+        let undo = board.make_move(mv.clone());
+        let is_legal = !board.leaves_king_in_check(mv.piece_color());
+
+        board.unmake_move(mv.clone(), undo);
+
+        is_legal
+    })
+}
+
+/// Generates the pseudo-legal moves that may get the side to move out of check.
+///
+/// These are king moves, captures of the checking piece, and moves blocking the
+/// line between a sliding checker and the king. In double check only the king can
+/// move. The moves still have to be filtered like the ones of [`all_moves`], e.g.
+/// a blocking piece may be pinned, or the king may step onto an attacked square.
+fn evasion_moves(board: &Board) -> Vec<Move> {
+    let fren_color = board.side_to_move();
+    let opp_color = fren_color.opposing();
     let all_occ = board.all_occupancies();
-    let fren_color = match board.is_whites_turn {
-        true => Color::White,
-        false => Color::Black,
+    let fren_occ = board.occupied_by(fren_color);
+    let opp_occ = board.occupied_by(opp_color);
+
+    let mut moves = Vec::new();
+
+    add_king_moves_normal(board, fren_color, fren_occ, &mut moves);
+
+    let Some(king_idx) = board.king_square(fren_color).map(usize::from) else {
+        return moves;
+    };
+
+    let diagonal_checkers = piece::get_bishop_attacks_for(king_idx, all_occ)
+        & (board.bishops[opp_color] | board.queens[opp_color]);
+    let straight_checkers = piece::get_rook_attacks_for(king_idx, all_occ)
+        & (board.rooks[opp_color] | board.queens[opp_color]);
+    let checkers = diagonal_checkers
+        | straight_checkers
+        | (piece::get_knight_attack_mask_for(king_idx) & board.knights[opp_color])
+        | (piece::get_pawn_attacks_for(king_idx, &fren_color) & board.pawns[opp_color]);
+
+    if bit_board::count_set_bits(checkers) != 1 {
+        return moves;
+    }
+
+    let checker_idx = bit_board::get_first_set_bit(checkers).unwrap();
+    let checker_bit = bit_board::with_bit_at(checker_idx);
+    let king_bit = bit_board::with_bit_at(king_idx);
+    let between = if bit_board::has_set_bits(diagonal_checkers) {
+        piece::get_bishop_attacks_for(king_idx, checker_bit)
+            & piece::get_bishop_attacks_for(checker_idx, king_bit)
+    } else if bit_board::has_set_bits(straight_checkers) {
+        piece::get_rook_attacks_for(king_idx, checker_bit)
+            & piece::get_rook_attacks_for(checker_idx, king_bit)
+    } else {
+        0
+    };
+    // The helpers exclude the passed occupancies from the destinations.
+    let non_target = !(checker_bit | between);
+
+    add_bishop_moves(board, fren_color, all_occ, non_target, &mut moves);
+    add_knight_moves(
+        board.knights[fren_color],
+        non_target,
+        fren_color,
+        &mut moves,
+    );
+    add_queen_moves(board, fren_color, all_occ, non_target, &mut moves);
+    add_rook_moves(board, fren_color, all_occ, non_target, &mut moves);
+
+    let mut pawn_moves = Vec::new();
+
+    add_pawn_moves(
+        board,
+        board.pawns[fren_color],
+        all_occ,
+        opp_occ,
+        fren_color,
+        &mut pawn_moves,
+    );
+
+    moves.extend(pawn_moves.into_iter().filter(|mv| {
+        // An en passant capture doesn't land on the captured (checking) pawn.
+        let captured_idx = match (mv.is_en_passant(), fren_color) {
+            (true, White) => mv.dst() + SOUTH,
+            (true, Black) => mv.dst() - NORTH,
+            (false, _) => mv.dst(),
+        };
+
+        captured_idx == checker_idx || bit_board::is_bit_set(between, mv.dst())
+    }));
+
+    mark_captures(&mut moves, opp_occ);
+
+    moves
+}
+
+/// Generates the legal moves of the piece on the given square.
+///
+/// Only the moves of that single piece are generated. If the square is empty, or
+/// the piece doesn't belong to the side to move, no moves are returned.
+pub fn moves_from(board: &Board, src: impl BoardPos) -> Vec<Move> {
+    let src = src.into();
+    let fren_color = board.side_to_move();
+
+    let piece = match board.get(src) {
+        Some(ins) if ins.color == fren_color => ins.piece,
+        _ => return Vec::new(),
     };
+
+    let all_occ = board.all_occupancies();
     let opp_color = fren_color.opposing();
-    // TODO: replace with `board.occupancies_of(fren_color)`
-    let fren_occ = board.bishops[fren_color]
-        | board.king[fren_color]
-        | board.knights[fren_color]
-        | board.pawns[fren_color]
-        | board.queens[fren_color]
-        | board.rooks[fren_color];
-    // TODO: replace with `board.occupancies_of(opp_color)`
-    let opp_occupancies = board.bishops[opp_color]
-        | board.king[opp_color]
-        | board.knights[opp_color]
-        | board.pawns[opp_color]
-        | board.queens[opp_color]
-        | board.rooks[opp_color];
+    let fren_occ = board.occupied_by(fren_color);
+    let src_bit = bit_board::with_bit_at(src);
+
+    let mut moves = Vec::new();
 
+    match piece {
+        Bishop => add_sliding_moves(
+            src_bit,
+            all_occ,
+            piece::get_bishop_attacks_for,
+            fren_occ,
+            fren_color,
+            Bishop,
+            &mut moves,
+        ),
+        King => add_king_moves(board, fren_color, fren_occ, all_occ, opp_color, &mut moves),
+        Knight => add_knight_moves(src_bit, fren_occ, fren_color, &mut moves),
+        Pawn => add_pawn_moves(
+            board,
+            src_bit,
+            all_occ,
+            board.occupied_by(opp_color),
+            fren_color,
+            &mut moves,
+        ),
+        Queen => add_sliding_moves(
+            src_bit,
+            all_occ,
+            piece::get_queen_attacks_for,
+            fren_occ,
+            fren_color,
+            Queen,
+            &mut moves,
+        ),
+        Rook => add_sliding_moves(
+            src_bit,
+            all_occ,
+            piece::get_rook_attacks_for,
+            fren_occ,
+            fren_color,
+            Rook,
+            &mut moves,
+        ),
+    }
+
+    moves.retain(|mv| board.clone().do_move(mv.clone()));
+    mark_captures(&mut moves, board.occupied_by(opp_color));
+
+    moves
+}
+
+/// Generates the pseudo-legal moves of all pieces of the given type, for the side
+/// to move.
+///
+/// Same as filtering [`all_moves`] by [`Move::piece`], but only the moves of that
+/// piece type are generated in the first place.
+pub fn moves_for_piece(board: &Board, piece: Piece) -> Vec<Move> {
     let mut moves = Vec::new();
 
-    add_bishop_moves(board, fren_color, all_occ, fren_occ, &mut moves);
-    add_king_moves(board, fren_color, fren_occ, all_occ, opp_color, &mut moves);
-    add_knight_moves(board, fren_occ, fren_color, &mut moves);
-    add_pawn_moves(board, all_occ, opp_occupancies, fren_color, &mut moves);
-    add_queen_moves(board, fren_color, all_occ, fren_occ, &mut moves);
-    add_rook_moves(board, fren_color, all_occ, fren_occ, &mut moves);
+    add_moves_of(board, piece, &mut moves);
+
+    moves
+}
+
+/// Appends the pseudo-legal moves of all pieces of the given type, for the side to
+/// move, with their captures marked.
+fn add_moves_of(board: &Board, piece: Piece, out: &mut Vec<Move>) {
+    let all_occ = board.all_occupancies();
+    let fren_color = board.side_to_move();
+    let opp_color = fren_color.opposing();
+    let fren_occ = board.occupied_by(fren_color);
+    let opp_occ = board.occupied_by(opp_color);
+    let start = out.len();
+
+    match piece {
+        Bishop => add_bishop_moves(board, fren_color, all_occ, fren_occ, out),
+        King => add_king_moves(board, fren_color, fren_occ, all_occ, opp_color, out),
+        Knight => add_knight_moves(board.knights[fren_color], fren_occ, fren_color, out),
+        Pawn => add_pawn_moves(
+            board,
+            board.pawns[fren_color],
+            all_occ,
+            opp_occ,
+            fren_color,
+            out,
+        ),
+        Queen => add_queen_moves(board, fren_color, all_occ, fren_occ, out),
+        Rook => add_rook_moves(board, fren_color, all_occ, fren_occ, out),
+    }
+
+    mark_captures(&mut out[start..], opp_occ);
+}
+
+/// Counts the leaf nodes of the legal move tree, `depth` plies deep.
+///
+/// Perft (*perf*ormance *t*est) results can be compared to validated ones to
+/// find bugs in the move generation.
+///
+/// For more information, visit: https://www.chessprogramming.org/Perft
+pub fn perft(board: &Board, depth: usize) -> u64 {
+    return perft_recursive(&mut board.clone(), depth);
+
+    fn perft_recursive(board: &mut Board, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        // Bulk counting, there is no need to execute the moves of the last ply.
+        if depth == 1 {
+            return count_legal_moves(board) as u64;
+        }
+
+        let mut nodes = 0;
+
+        for mv in legal_moves(board) {
+            let undo = board.make_move(mv.clone());
+            nodes += perft_recursive(board, depth - 1);
+            board.unmake_move(mv, undo);
+        }
+
+        nodes
+    }
+}
+
+/// Same as [`perft`], but distributes the root moves across multiple threads.
+///
+/// Every thread counts the subtrees of its root moves on its own copy of the
+/// board. The result is identical to the one of [`perft`].
+pub fn perft_parallel(board: &Board, depth: usize) -> u64 {
+    if depth <= 1 {
+        return perft(board, depth);
+    }
+
+    let moves = legal_moves(board);
+    let thread_count = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = moves.len().div_ceil(thread_count).max(1);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = moves
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut board = board.clone();
+
+                    chunk
+                        .iter()
+                        .map(|mv| {
+                            let undo = board.make_move(mv.clone());
+                            let nodes = perft(&board, depth - 1);
+                            board.unmake_move(mv.clone(), undo);
+
+                            nodes
+                        })
+                        .sum::<u64>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("perft thread panicked"))
+            .sum()
+    })
+}
+
+/// Same as [`perft`], but additionally classifies the leaf nodes by the move that
+/// led to them (see [`PerftStats`]).
+///
+/// The breakdown can be compared to the tables on chessprogramming.org, which
+/// makes it easier to localize bugs.
+pub fn perft_detailed(board: &Board, depth: usize) -> PerftStats {
+    let mut stats = PerftStats::default();
+
+    if depth == 0 {
+        stats.nodes = 1;
+        return stats;
+    }
+
+    perft_detailed_recursive(&mut board.clone(), depth, &mut stats);
+
+    return stats;
+
+    fn perft_detailed_recursive(board: &mut Board, depth: usize, stats: &mut PerftStats) {
+        for mv in legal_moves(board) {
+            if depth > 1 {
+                let undo = board.make_move(mv.clone());
+                perft_detailed_recursive(board, depth - 1, stats);
+                board.unmake_move(mv, undo);
+
+                continue;
+            }
+
+            stats.nodes += 1;
+            stats.captures += mv.is_capture() as u64;
+            stats.en_passants += mv.is_en_passant() as u64;
+            stats.castles += mv.is_castle() as u64;
+            stats.promotions += mv.prom_to().is_some() as u64;
+
+            let undo = board.make_move(mv.clone());
+            if board.is_in_check() {
+                stats.checks += 1;
+                stats.checkmates += legal_moves(board).is_empty() as u64;
+            }
+            board.unmake_move(mv, undo);
+        }
+    }
+}
+
+/// Same as [`perft`], but returns the node count for each root move separately.
+///
+/// The moves are sorted by their UCI notation (see [`to_uci`]), which makes it
+/// easy to compare the result with the "divide" output of other engines.
+pub fn perft_divide(board: &Board, depth: usize) -> Vec<(Move, u64)> {
+    let mut board = board.clone();
+
+    let mut divide: Vec<_> = legal_moves(&board)
+        .into_iter()
+        .map(|mv| {
+            let undo = board.make_move(mv.clone());
+            let nodes = perft(&board, depth.saturating_sub(1));
+            board.unmake_move(mv.clone(), undo);
+
+            (mv, nodes)
+        })
+        .collect();
+
+    divide.sort_by_cached_key(|(mv, _)| to_uci(mv));
+
+    divide
+}
+
+/// Evaluates if the move puts the king of the opponent in check.
+///
+/// The move is made on a copy of the board, so discovered checks, the rook of a
+/// castling move, and promoted pieces are all taken into account.
+pub fn gives_check(board: &Board, mv: &Move) -> bool {
+    let mut board = board.clone();
+    board.make_move(mv.clone());
+
+    board.is_in_check()
+}
+
+/// Converts a move into the standard algebraic notation (SAN), e.g. `Nf3`, `exd5`,
+/// `O-O`, or `e8=Q+`.
+///
+/// The move has to be a legal move on the given board, which is used to detect
+/// captures, ambiguities, and checks.
+///
+/// For more information, visit: https://en.wikipedia.org/wiki/Algebraic_notation_(chess)
+pub fn to_san(board: &Board, mv: &Move) -> String {
+    let mut san = String::new();
+
+    let src_sym = FEN_SQUARE_SYMBOL_LOOKUP[mv.src()];
+    let dst_sym = FEN_SQUARE_SYMBOL_LOOKUP[mv.dst()];
+    let is_capture = mv.is_en_passant()
+        || bit_board::is_bit_set(board.occupied_by(mv.piece_color().opposing()), mv.dst());
+
+    if mv.is_castle() {
+        san += match mv.dst() % Board::WIDTH {
+            2 /* C file */ => "O-O-O",
+            _ => "O-O",
+        };
+    } else if mv.piece() == Pawn {
+        if is_capture {
+            san.push(src_sym.as_bytes()[0] as char);
+            san.push('x');
+        }
+
+        san += dst_sym;
+
+        if let Some(prom_to) = mv.prom_to() {
+            san.push('=');
+            san.push(prom_to.to_char());
+        }
+    } else {
+        san.push(mv.piece().to_char());
+
+        // Other pieces of the same type that could move to the same square.
+        let ambiguous_srcs: Vec<_> = legal_moves(board)
+            .into_iter()
+            .filter(|other| {
+                other.piece() == mv.piece() && other.dst() == mv.dst() && other.src() != mv.src()
+            })
+            .map(|other| other.src())
+            .collect();
+
+        if !ambiguous_srcs.is_empty() {
+            let same_file = |i: &usize| i % Board::WIDTH == mv.src() % Board::WIDTH;
+            let same_rank = |i: &usize| i / Board::HEIGHT == mv.src() / Board::HEIGHT;
+
+            if !ambiguous_srcs.iter().any(same_file) {
+                san.push_str(&src_sym[..1]);
+            } else if !ambiguous_srcs.iter().any(same_rank) {
+                san.push_str(&src_sym[1..]);
+            } else {
+                san.push_str(src_sym);
+            }
+        }
+
+        if is_capture {
+            san.push('x');
+        }
+
+        san += dst_sym;
+    }
+
+    let mut board = board.clone();
+    board.do_move(mv.clone());
+
+    if board.is_checkmate() {
+        san.push('#');
+    } else if board.is_in_check() {
+        san.push('+');
+    }
+
+    san
+}
+
+/// Resolves a move in the standard algebraic notation (SAN) against the legal
+/// moves of the given board.
+///
+/// Trailing annotations (`+`, `#`, `!`, `?`) are ignored. An error is returned if
+/// the SAN is malformed, ambiguous, or doesn't match any legal move.
+pub fn from_san(board: &Board, san: &str) -> Result<Move, String> {
+    let trimmed = san.trim_end_matches(['+', '#', '!', '?']);
+
+    let candidates: Vec<Move> = match trimmed {
+        "O-O" | "0-0" | "O-O-O" | "0-0-0" => {
+            let dst_file = if trimmed.len() == 3 { 6 } else { 2 };
+
+            legal_moves(board)
+                .into_iter()
+                .filter(|mv| mv.is_castle() && mv.dst() % Board::WIDTH == dst_file)
+                .collect()
+        }
+        _ => {
+            let mut chars: Vec<char> = trimmed.chars().collect();
+
+            // Lowercase letters are files, so only uppercase ones denote pieces.
+            let san_piece = |c: &char| {
+                Piece::from_char(*c).filter(|piece| c.is_ascii_uppercase() && *piece != Pawn)
+            };
+
+            let piece = match chars.first().and_then(san_piece) {
+                Some(piece) => {
+                    chars.remove(0);
+                    piece
+                }
+                None => Pawn,
+            };
+
+            let prom_to = match chars.last().and_then(san_piece) {
+                Some(prom_to) => {
+                    chars.pop();
+                    if chars.last() == Some(&'=') {
+                        chars.pop();
+                    }
+                    Some(prom_to)
+                }
+                None => None,
+            };
+
+            chars.retain(|c| *c != 'x');
+
+            if chars.len() < 2 {
+                return Err(format!("'{}' is not a valid SAN move", san));
+            }
+
+            let dst_sym: String = chars.split_off(chars.len() - 2).into_iter().collect();
+            let dst = FEN_SQUARE_SYMBOL_LOOKUP
+                .iter()
+                .position(|sym| *sym == dst_sym)
+                .ok_or_else(|| format!("'{}' is not a valid SAN move", san))?;
+
+            // Whatever is left, is used to disambiguate the source square.
+            let mut src_file = None;
+            let mut src_rank = None;
+            for c in chars {
+                match c {
+                    'a'..='h' => src_file = Some(c as usize - 'a' as usize),
+                    '1'..='8' => src_rank = Some(Board::HEIGHT - (c as usize - '0' as usize)),
+                    _ => return Err(format!("'{}' is not a valid SAN move", san)),
+                }
+            }
+
+            legal_moves(board)
+                .into_iter()
+                .filter(|mv| {
+                    !mv.is_castle()
+                        && mv.piece() == piece
+                        && mv.dst() == dst
+                        && mv.prom_to() == prom_to
+                        && src_file.is_none_or(|file| mv.src() % Board::WIDTH == file)
+                        && src_rank.is_none_or(|rank| mv.src() / Board::HEIGHT == rank)
+                })
+                .collect()
+        }
+    };
+
+    match candidates.len() {
+        0 => Err(format!("'{}' does not match any legal move", san)),
+        1 => Ok(candidates.into_iter().next().unwrap()),
+        _ => Err(format!(
+            "'{}' is ambiguous, it matches: {}",
+            san,
+            candidates
+                .iter()
+                .map(|mv| mv.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Formats the move in the UCI long algebraic notation (e.g. `e2e4` or `e7e8q`).
+pub fn to_uci(mv: &Move) -> String {
+    let mut uci = format!(
+        "{}{}",
+        FEN_SQUARE_SYMBOL_LOOKUP[mv.src()],
+        FEN_SQUARE_SYMBOL_LOOKUP[mv.dst()]
+    );
+
+    if let Some(prom_to) = mv.prom_to() {
+        uci.push(prom_to.to_char().to_ascii_lowercase());
+    }
+
+    uci
+}
+
+/// Resolves a move in the UCI long algebraic notation against the legal moves of
+/// the given board.
+///
+/// Flags like castling, en passant, or double push are inferred from the board.
+pub fn from_uci(board: &Board, uci: &str) -> Result<Move, String> {
+    if !uci.is_ascii() || (uci.len() != 4 && uci.len() != 5) {
+        return Err(format!(
+            "'{}' is not a valid UCI move, expected 4 or 5 characters",
+            uci
+        ));
+    }
+
+    let square = |sym: &str| {
+        FEN_SQUARE_SYMBOL_LOOKUP
+            .iter()
+            .position(|s| *s == sym)
+            .ok_or_else(|| {
+                format!(
+                    "'{}' is not a valid UCI move, unknown square '{}'",
+                    uci, sym
+                )
+            })
+    };
+
+    let src = square(&uci[0..2])?;
+    let dst = square(&uci[2..4])?;
+    let prom_to = match uci.get(4..) {
+        None | Some("") => None,
+        Some("b") => Some(Bishop),
+        Some("n") => Some(Knight),
+        Some("q") => Some(Queen),
+        Some("r") => Some(Rook),
+        Some(unknown) => {
+            return Err(format!(
+                "'{}' is not a valid UCI move, unknown promotion '{}'",
+                uci, unknown
+            ))
+        }
+    };
 
-    return moves;
+    legal_moves(board)
+        .into_iter()
+        .find(|mv| mv.src() == src && mv.dst() == dst && mv.prom_to() == prom_to)
+        .ok_or_else(|| format!("'{}' does not match any legal move", uci))
 }
 
 fn add_bishop_moves(
@@ -88,20 +736,23 @@ fn add_king_moves(
         moves.push(Move::new_castle(fren_color, src, dst));
     };
 
-    // TODO: Check if it's actually blacks turn
-    if board.can_black_castle_queen_side {
-        castle(14, &[C8, D8, E8], E8, C8);
-    }
-    if board.can_black_castle_king_side {
-        castle(96, &[E8, F8, G8], E8, G8);
-    }
-
-    // TODO: Check if it's actually whites turn
-    if board.can_white_castle_queen_side {
-        castle(1008806316530991104, &[C1, D1, E1], E1, C1);
-    }
-    if board.can_white_castle_king_side {
-        castle(6917529027641081856, &[E1, F1, G1], E1, G1);
+    match fren_color {
+        Black => {
+            if board.castling_rights().has(Black, CastlingSide::QueenSide) {
+                castle(14, &[C8, D8, E8], E8, C8);
+            }
+            if board.castling_rights().has(Black, CastlingSide::KingSide) {
+                castle(96, &[E8, F8, G8], E8, G8);
+            }
+        }
+        White => {
+            if board.castling_rights().has(White, CastlingSide::QueenSide) {
+                castle(1008806316530991104, &[C1, D1, E1], E1, C1);
+            }
+            if board.castling_rights().has(White, CastlingSide::KingSide) {
+                castle(6917529027641081856, &[E1, F1, G1], E1, G1);
+            }
+        }
     }
 
     add_king_moves_normal(board, fren_color, fren_occ, moves);
@@ -115,83 +766,95 @@ fn add_king_moves_normal(board: &Board, fren_color: Color, fren_occ: u64, moves:
     }
 }
 
-fn add_knight_moves(board: &Board, fren_occ: u64, fren_color: Color, moves: &mut Vec<Move>) {
-    for src_i in SetBitsIter(board.knights[fren_color]) {
+fn add_knight_moves(knights: u64, fren_occ: u64, fren_color: Color, moves: &mut Vec<Move>) {
+    for src_i in SetBitsIter(knights) {
         for dst_i in SetBitsIter(piece::get_knight_attack_mask_for(src_i) & !fren_occ) {
             moves.push(Move::new(fren_color, Knight, src_i, dst_i));
         }
     }
 }
 
+fn add_pawn_captures(
+    board: &Board,
+    pawns: u64,
+    opp_occupancies: u64,
+    fren_color: Color,
+    moves: &mut Vec<Move>,
+) {
+    let is_prom: fn(usize) -> bool = match fren_color {
+        Black => is_black_prom,
+        White => is_white_prom,
+    };
+
+    for src_i in SetBitsIter(pawns) {
+        let attacks = piece::get_pawn_attacks_for(src_i, &fren_color);
+
+        for capture in SetBitsIter(attacks & opp_occupancies) {
+            if is_prom(capture) {
+                // Capturing promotions
+                moves.push(Move::new_prom(fren_color, src_i, capture, Bishop));
+                moves.push(Move::new_prom(fren_color, src_i, capture, Knight));
+                moves.push(Move::new_prom(fren_color, src_i, capture, Queen));
+                moves.push(Move::new_prom(fren_color, src_i, capture, Rook));
+            } else {
+                moves.push(Move::new(fren_color, Pawn, src_i, capture));
+            }
+        }
+
+        // En passant
+        if let Some(en_passant_target_idx) = board.en_passant_square().map(usize::from) {
+            if bit_board::is_bit_set(attacks, en_passant_target_idx) {
+                moves.push(Move::new_en_pass(fren_color, src_i, en_passant_target_idx));
+            }
+        }
+    }
+}
+
 fn add_pawn_moves(
     board: &Board,
+    pawns: u64,
     all_occupancies: u64,
     opp_occupancies: u64,
     fren_color: Color,
     moves: &mut Vec<Move>,
 ) {
-    let (dir, can_do_double_push, is_prom): (_, fn(usize) -> bool, fn(usize) -> bool) =
-        match fren_color {
-            Black => (SOUTH as i8, can_black_do_dbl_push, is_black_prom),
-            White => (-(NORTH as i8), can_white_do_dbl_push, is_white_prom),
-        };
+    type IdxPredicate = fn(usize) -> bool;
 
-    let pawns = board.pawns[fren_color];
+    let (dir, can_do_double_push, is_prom): (_, IdxPredicate, IdxPredicate) = match fren_color {
+        Black => (SOUTH as i8, can_black_do_dbl_push, is_black_prom),
+        White => (-(NORTH as i8), can_white_do_dbl_push, is_white_prom),
+    };
 
-    // TODO: refactor candidate?
     for src_i in SetBitsIter(pawns) {
         let dst_i = (src_i as i8 + dir) as usize;
 
+        if bit_board::is_bit_set(all_occupancies, dst_i) {
+            continue;
+        }
+
         if is_prom(dst_i) {
             // Promotions
-            if !bit_board::is_bit_set(all_occupancies, dst_i) {
-                moves.push(Move::new_prom(fren_color, src_i, dst_i, Bishop));
-                moves.push(Move::new_prom(fren_color, src_i, dst_i, Knight));
-                moves.push(Move::new_prom(fren_color, src_i, dst_i, Queen));
-                moves.push(Move::new_prom(fren_color, src_i, dst_i, Rook));
-            }
-
-            // Capturing promotions
-            let captures = piece::get_pawn_attacks_for(src_i, &fren_color) & opp_occupancies;
-            for capture in SetBitsIter(captures) {
-                moves.push(Move::new_prom(fren_color, src_i, capture, Bishop));
-                moves.push(Move::new_prom(fren_color, src_i, capture, Knight));
-                moves.push(Move::new_prom(fren_color, src_i, capture, Queen));
-                moves.push(Move::new_prom(fren_color, src_i, capture, Rook));
-            }
+            moves.push(Move::new_prom(fren_color, src_i, dst_i, Bishop));
+            moves.push(Move::new_prom(fren_color, src_i, dst_i, Knight));
+            moves.push(Move::new_prom(fren_color, src_i, dst_i, Queen));
+            moves.push(Move::new_prom(fren_color, src_i, dst_i, Rook));
         } else {
             // Push
-            if !bit_board::is_bit_set(all_occupancies, dst_i) {
-                moves.push(Move::new(fren_color, Pawn, src_i, dst_i));
+            moves.push(Move::new(fren_color, Pawn, src_i, dst_i));
 
-                // Double push
-                if can_do_double_push(src_i) {
-                    let dst_idx = (src_i as i8 + dir * 2) as usize;
+            // Double push
+            if can_do_double_push(src_i) {
+                let dst_idx = (src_i as i8 + dir * 2) as usize;
 
-                    if !bit_board::is_bit_set(all_occupancies, dst_idx) {
-                        moves.push(Move::new_dbl_push(fren_color, src_i, dst_idx));
-                    }
-                }
-            }
-
-            // Captures
-            let captures = piece::get_pawn_attacks_for(src_i, &fren_color) & opp_occupancies;
-            for capture in SetBitsIter(captures) {
-                moves.push(Move::new(fren_color, Pawn, src_i, capture));
-            }
-
-            // En passant
-            if let Some(en_passant_target_idx) = board.en_passant_target_idx {
-                if bit_board::is_bit_set(
-                    piece::get_pawn_attacks_for(src_i, &fren_color),
-                    en_passant_target_idx,
-                ) {
-                    moves.push(Move::new_en_pass(fren_color, src_i, en_passant_target_idx));
+                if !bit_board::is_bit_set(all_occupancies, dst_idx) {
+                    moves.push(Move::new_dbl_push(fren_color, src_i, dst_idx));
                 }
             }
         }
     }
 
+    add_pawn_captures(board, pawns, opp_occupancies, fren_color, moves);
+
     fn can_black_do_dbl_push(i: usize) -> bool {
         i > usize::from(A7) - 1 && i < usize::from(H7) + 1
     }
@@ -199,14 +862,14 @@ fn add_pawn_moves(
     fn can_white_do_dbl_push(i: usize) -> bool {
         i > 47 && i < 56
     }
+}
 
-    fn is_white_prom(i: usize) -> bool {
-        i < 8
-    }
+fn is_white_prom(i: usize) -> bool {
+    i < 8
+}
 
-    fn is_black_prom(i: usize) -> bool {
-        i > 55 && i < 64
-    }
+fn is_black_prom(i: usize) -> bool {
+    i > 55 && i < 64
 }
 
 fn add_queen_moves(
@@ -245,6 +908,17 @@ fn add_rook_moves(
     );
 }
 
+/// Flags the moves landing on an opponent piece as captures.
+///
+/// En passant captures are always flagged by [`Move::new_en_pass`].
+fn mark_captures(moves: &mut [Move], opp_occ: u64) {
+    for mv in moves {
+        if bit_board::is_bit_set(opp_occ, mv.dst()) {
+            mv.set_is_capture(true);
+        }
+    }
+}
+
 fn squares_attacked_by(squares: &[Square], board: &Board, color: Color) -> bool {
     for square in squares {
         if board.is_pos_attacked_by(*square, &color) {
@@ -277,7 +951,7 @@ mod tests {
 
     use pretty_assertions::assert_eq;
 
-    use crate::fen::Fen;
+    use crate::fen::{Fen, STARTING_FEN};
 
     use super::*;
 
@@ -287,7 +961,7 @@ mod tests {
             let mut board = Board::new_empty();
             board.set(Color::White, Piece::Pawn, src);
 
-            assert_moves_eq(&all_moves(&board), &vec![Move::new(White, Pawn, src, dst)]);
+            assert_moves_eq(&all_moves(&board), &[Move::new(White, Pawn, src, dst)]);
         }
     }
 
@@ -295,10 +969,10 @@ mod tests {
     fn black_pawn_push() {
         for (src, dst) in [(A6, A5), (B6, B5)] {
             let mut board = Board::new_empty();
-            board.is_whites_turn = false;
+            board.set_side_to_move(Black);
             board.set(Black, Pawn, src);
 
-            assert_moves_eq(&all_moves(&board), &vec![Move::new(Black, Pawn, src, dst)]);
+            assert_moves_eq(&all_moves(&board), &[Move::new(Black, Pawn, src, dst)]);
         }
     }
 
@@ -312,7 +986,7 @@ mod tests {
 
             for src_idx in src_range {
                 let mut board = Board::new_empty();
-                board.is_whites_turn = color == White;
+                board.set_side_to_move(color);
                 board.set(color, Pawn, src_idx);
 
                 let (dst, dst_dbl) = match color {
@@ -322,7 +996,7 @@ mod tests {
 
                 assert_moves_eq(
                     &all_moves(&board),
-                    &vec![
+                    &[
                         Move::new(color, Pawn, src_idx, dst),
                         Move::new_dbl_push(color, src_idx, dst_dbl),
                     ],
@@ -341,7 +1015,7 @@ mod tests {
 
             for pos in poses {
                 let mut board = Board::new_empty();
-                board.is_whites_turn = color == White;
+                board.set_side_to_move(color);
                 board.set(color, Pawn, pos);
 
                 assert_eq!(all_moves(&board).len(), 1);
@@ -382,7 +1056,7 @@ mod tests {
     fn black_pawn_promotion() {
         for i in 49..54usize {
             let mut board = Board::new_empty();
-            board.is_whites_turn = false;
+            board.set_side_to_move(Black);
             board.set(Color::Black, Piece::Pawn, i);
 
             assert_moves_eq(
@@ -401,7 +1075,7 @@ mod tests {
     fn black_pawn_promotion_blocked() {
         for i in 56..64 {
             let mut board = Board::new_empty();
-            board.is_whites_turn = false;
+            board.set_side_to_move(Black);
             board.set(Color::White, Piece::Pawn, i);
             board.set(Color::Black, Piece::Pawn, i - bit_board::NORTH);
 
@@ -440,9 +1114,9 @@ mod tests {
     #[test]
     fn pawn_capture() {
         for (color, attacks) in [(Black, [D5, F5]), (White, [D7, F7])] {
-            for piece_to_cap in Piece::all_variants() {
+            for piece_to_cap in Piece::all() {
                 let mut board = Board::new_empty();
-                board.is_whites_turn = color == White;
+                board.set_side_to_move(color);
                 board.set(color, Pawn, E6);
 
                 let mut exp_moves = vec![match color {
@@ -460,6 +1134,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn white_pawn_captures_knight() {
+        let board = Board::from_fen("4k3/8/8/3n4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let moves = legal_moves(&board);
+        let capture = moves
+            .iter()
+            .find(|mv| mv.src_square() == E4 && mv.dst_square() == D5)
+            .unwrap();
+
+        assert!(capture.is_capture());
+    }
+
     #[test]
     fn white_pawn_capture_promotion() {
         let mut board = Board::new_empty();
@@ -486,10 +1172,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn white_pawn_capture_promotion_every_piece() {
+        for piece_to_cap in [Bishop, Knight, Pawn, Queen, Rook] {
+            let mut board = Board::new_empty();
+            board.set(Black, piece_to_cap, A8);
+            board.set(Black, King, B8);
+            board.set(White, Pawn, B7);
+
+            let moves = all_moves(&board);
+
+            assert_moves_eq(
+                &moves,
+                &[
+                    Move::new_prom(White, B7, A8, Bishop),
+                    Move::new_prom(White, B7, A8, Knight),
+                    Move::new_prom(White, B7, A8, Queen),
+                    Move::new_prom(White, B7, A8, Rook),
+                ],
+            );
+            assert!(moves.iter().all(Move::is_capture), "{:?}", piece_to_cap);
+        }
+    }
+
     #[test]
     fn black_pawn_capture_promotion() {
         let mut board = Board::new_empty();
-        board.is_whites_turn = false;
+        board.set_side_to_move(Black);
         board.set(Black, Pawn, B2);
         board.set(White, Rook, A1);
         board.set(White, Bishop, C1);
@@ -519,7 +1228,7 @@ mod tests {
     fn white_pawn_en_passant() {
         for i in 24..31 {
             let mut board = Board::new_empty();
-            board.en_passant_target_idx = Some(i - bit_board::NORTH);
+            board.set_en_passant_square(Some(Square::ALL[i - bit_board::NORTH]));
             board.set(Color::Black, Piece::Pawn, i);
             board.set(Color::White, Piece::Pawn, i + bit_board::EAST);
 
@@ -537,8 +1246,8 @@ mod tests {
     fn black_pawn_en_passant() {
         for i in 32..39 {
             let mut board = Board::new_empty();
-            board.is_whites_turn = false;
-            board.en_passant_target_idx = Some(i + bit_board::SOUTH);
+            board.set_side_to_move(Black);
+            board.set_en_passant_square(Some(Square::ALL[i + bit_board::SOUTH]));
             board.set(Color::White, Piece::Pawn, i);
             board.set(Color::Black, Piece::Pawn, i + bit_board::EAST);
 
@@ -595,7 +1304,7 @@ mod tests {
             ),
         ] {
             let mut board = Board::new_empty();
-            board.is_whites_turn = color == White;
+            board.set_side_to_move(color);
             board.set(color, King, king_pos);
             board.set(color, Pawn, blocker_pos);
 
@@ -642,7 +1351,7 @@ mod tests {
 
     #[test]
     fn white_king_queen_side_castle_blocked() {
-        let board = Board::from_fen("8/8/8/8/8/8/8/3K3 w Q - 0 0").unwrap();
+        let board = Board::from_fen("8/8/8/8/8/8/8/R3K3 w Q - 0 0").unwrap();
 
         for i in 57..60 {
             let mut board = board.clone();
@@ -895,7 +1604,7 @@ mod tests {
     #[test]
     fn black_knight_only_black() {
         let mut board = Board::new_empty();
-        board.is_whites_turn = false;
+        board.set_side_to_move(Black);
         board.set(White, Knight, C6);
         board.set(Black, Knight, G8);
 
@@ -939,7 +1648,7 @@ mod tests {
     #[test]
     fn black_bishop() {
         let mut board = Board::new_empty();
-        board.is_whites_turn = false;
+        board.set_side_to_move(Black);
         board.set(Black, Bishop, F8);
         board.set(Black, Bishop, H3);
 
@@ -986,7 +1695,7 @@ mod tests {
     #[test]
     fn black_bishop_blocked() {
         let mut board = Board::new_empty();
-        board.is_whites_turn = false;
+        board.set_side_to_move(Black);
         board.set(Black, Bishop, C2);
         board.set(White, Bishop, B3);
         board.set(White, Bishop, E4);
@@ -1039,7 +1748,7 @@ mod tests {
     #[test]
     fn black_queen() {
         let mut board = Board::new_empty();
-        board.is_whites_turn = false;
+        board.set_side_to_move(Black);
         board.set(Black, Queen, D8);
 
         println!("{}", board);
@@ -1075,7 +1784,7 @@ mod tests {
     #[test]
     fn black_queen_blocked() {
         let mut board = Board::new_empty();
-        board.is_whites_turn = false;
+        board.set_side_to_move(Black);
         board.set(Black, Queen, H4);
         board.set(Black, Pawn, H3);
         board.set(White, Queen, E4);
@@ -1194,7 +1903,7 @@ mod tests {
     #[test]
     fn black_rook() {
         let mut board = Board::new_empty();
-        board.is_whites_turn = false;
+        board.set_side_to_move(Black);
         board.set(Black, Rook, A8);
         board.set(Black, Rook, H1);
 
@@ -1236,7 +1945,7 @@ mod tests {
     #[test]
     fn black_rook_blocked() {
         let mut board = Board::new_empty();
-        board.is_whites_turn = false;
+        board.set_side_to_move(Black);
         board.set(Black, Rook, H8);
         board.set(Black, Pawn, H6);
         board.set(White, Rook, E8);
@@ -1253,43 +1962,781 @@ mod tests {
         );
     }
 
-    fn assert_moves_eq(left: &[Move], right: &[Move]) {
-        let mut left = left.to_vec();
-        left.sort_by(display_value);
-        let mut right = right.to_vec();
-        right.sort_by(display_value);
-
-        assert_eq!(
-            left,
-            right,
-            "\nexpected:\n\t{}\nto equal:\n\t{}\n",
-            display(&left),
-            display(&right),
-        );
+    #[test]
+    fn moves_for_piece_knights_starting_position() {
+        let board = Board::new_with_standard_formation();
+        let moves = moves_for_piece(&board, Knight);
+        let expected = [
+            Move::between(White, Knight, B1, A3),
+            Move::between(White, Knight, B1, C3),
+            Move::between(White, Knight, G1, F3),
+            Move::between(White, Knight, G1, H3),
+        ];
 
-        fn display(moves: &[Move]) -> String {
-            let display_val = moves
-                .iter()
-                .map(|m| format!("{}", m))
-                .collect::<Vec<_>>()
-                .join(", ");
+        assert_eq!(moves.len(), expected.len());
+        assert!(expected.iter().all(|mv| moves.contains(mv)));
+    }
 
-            if display_val.chars().count() == 0 {
-                return "<no moves>".to_owned();
-            }
+    #[test]
+    fn moves_for_piece_matches_filtered_all_moves() {
+        for fen in [
+            STARTING_FEN,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+            let all = all_moves(&board);
 
-            display_val
-        }
+            for piece in [Bishop, King, Knight, Pawn, Queen, Rook] {
+                let expected: Vec<_> = all.iter().filter(|mv| mv.piece() == piece).collect();
+                let moves = moves_for_piece(&board, piece);
 
-        fn display_value(a: &Move, b: &Move) -> Ordering {
-            format!("{}", a).cmp(&format!("{}", b))
+                assert_eq!(moves.len(), expected.len(), "{:?} in {}", piece, fen);
+                assert!(moves.iter().all(|mv| expected.contains(&mv)));
+            }
         }
     }
-}
 
-#[derive(PartialEq, Eq, Clone)]
+    #[test]
+    fn legal_moves_pinned_piece() {
+        let board = Board::from_fen("4r3/8/8/8/8/8/4B3/4K3 w - - 0 0").unwrap();
+
+        assert!(all_moves(&board).iter().any(|mv| mv.piece() == Bishop));
+        assert!(!legal_moves(&board).iter().any(|mv| mv.piece() == Bishop));
+    }
+
+    #[test]
+    fn legal_moves_king_not_moving_into_check() {
+        let board = Board::from_fen("8/8/8/8/8/8/3r4/K7 w - - 0 0").unwrap();
+
+        assert_moves_eq(&legal_moves(&board), &[Move::new(White, King, A1, B1)]);
+    }
+
+    #[test]
+    fn legal_moves_en_passant_exposing_king_on_rank() {
+        let board = Board::from_fen("8/8/8/K2pP2r/8/8/8/7k w - d6 0 0").unwrap();
+
+        assert!(all_moves(&board).iter().any(|mv| mv.is_en_passant()));
+        assert!(!legal_moves(&board).iter().any(|mv| mv.is_en_passant()));
+    }
+
+    #[test]
+    fn castle_only_for_side_to_move() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+        let castles = all_moves(&board)
+            .into_iter()
+            .filter(|mv| mv.is_castle())
+            .collect::<Vec<_>>();
+
+        assert_moves_eq(
+            &castles,
+            &[
+                Move::new_castle(Black, E8, C8),
+                Move::new_castle(Black, E8, G8),
+            ],
+        );
+    }
+
+    #[test]
+    fn castle_queen_side_with_attacked_b_file() {
+        // The king never crosses B1 (B8), so it only has to be empty.
+        let board = Board::from_fen("1r2k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+
+        assert!(legal_moves(&board).contains(&Move::new_castle(White, E1, C1)));
+
+        let board = Board::from_fen("r3k3/8/8/8/8/8/8/1R2K3 b q - 0 1").unwrap();
+
+        assert!(legal_moves(&board).contains(&Move::new_castle(Black, E8, C8)));
+
+        // Attacking the square the king lands on or crosses prevents the castle.
+        for fen in [
+            "2r1k3/8/8/8/8/8/8/R3K3 w Q - 0 1",
+            "3rk3/8/8/8/8/8/8/R3K3 w Q - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+
+            assert!(
+                !legal_moves(&board).iter().any(|mv| mv.is_castle()),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn evasion_moves_match_filtered_all_moves() {
+        let fens = [
+            // Check by a rook, which can be blocked or captured.
+            "4r1k1/8/8/8/8/2N5/1B6/4K3 w - - 0 1",
+            // Double check, only the king can move.
+            "4r1k1/8/8/8/8/3n4/8/4K3 w - - 0 1",
+            // Check by a knight, which can't be blocked.
+            "4k3/8/8/8/8/3n4/1B6/4K2R w K - 0 1",
+            // Check by a pawn, which can be captured en passant.
+            "8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1",
+            // Blocking by promoting, while the blocking bishop is pinned.
+            "r3K2k/1P6/8/8/8/8/8/b3B3 w - - 0 1",
+            "r3k2r/p1ppqpb1/bn1Npnp1/3P4/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1",
+            "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+        ];
+
+        for fen in fens {
+            let board = Board::from_fen(fen).unwrap();
+            let filter = |moves: Vec<Move>| {
+                moves
+                    .into_iter()
+                    .filter(|mv| board.clone().do_move(mv.clone()))
+                    .collect::<Vec<_>>()
+            };
+
+            assert!(board.is_in_check(), "{}", fen);
+            assert_moves_eq(&filter(evasion_moves(&board)), &filter(all_moves(&board)));
+        }
+    }
+
+    #[test]
+    fn is_capture() {
+        let board =
+            Board::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/2N5/PPPP1PPP/R1BQKBNR w KQkq - 0 2")
+                .unwrap();
+        let moves = legal_moves(&board);
+        let find = |src: Square, dst: Square| {
+            moves
+                .iter()
+                .find(|mv| mv.src_square() == src && mv.dst_square() == dst)
+                .unwrap()
+        };
+
+        assert!(!find(E4, E5).is_capture());
+        assert!(!find(C3, B5).is_capture());
+        assert!(find(E4, D5).is_capture());
+        assert!(find(C3, D5).is_capture());
+        assert_eq!(moves.iter().filter(|mv| mv.is_capture()).count(), 2);
+
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let ep = legal_moves(&board)
+            .into_iter()
+            .find(|mv| mv.is_en_passant())
+            .unwrap();
+
+        assert!(ep.is_capture());
+        assert!(capture_moves(&board).iter().all(|mv| mv.is_capture()));
+    }
+
+    #[test]
+    fn packed_round_trip() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/Pp2P3/2N2Q1p/1PPBBPPP/R3K2R b KQkq a3 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+
+            for mv in legal_moves(&board) {
+                let unpacked = Move::from_packed(mv.to_packed(), &board);
+
+                assert_eq!(unpacked, mv, "{}", fen);
+                assert_eq!(unpacked.is_capture(), mv.is_capture(), "{}", mv);
+            }
+        }
+    }
+
+    #[test]
+    fn packed_layout() {
+        let packed = Move::new_prom(White, B7, A8, Rook).to_packed();
+
+        assert_eq!(packed & 0x3f, B7 as u16);
+        assert_eq!((packed >> 6) & 0x3f, A8 as u16);
+        assert_eq!(packed >> 12, PACKED_PROM_ROOK);
+    }
+
+    #[test]
+    fn count_legal_moves_equals_legal_moves_len() {
+        for fen in [
+            STARTING_FEN,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            // In check
+            "rnbqkbnr/ppppp1pp/8/5p1Q/4P3/8/PPPP1PPP/RNB1KBNR b KQkq - 1 2",
+            "4k3/8/8/8/8/5n2/8/R3K3 w - - 0 1",
+            // Checkmate
+            "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+
+            assert_eq!(
+                count_legal_moves(&board),
+                legal_moves(&board).len(),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn gives_check_direct() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+
+        assert!(gives_check(&board, &Move::new(White, Rook, A1, A8)));
+        assert!(!gives_check(&board, &Move::new(White, Rook, A1, A7)));
+    }
+
+    #[test]
+    fn gives_check_discovered() {
+        let board = Board::from_fen("4k3/8/8/8/4N3/8/8/4R1K1 w - - 0 1").unwrap();
+
+        assert!(gives_check(&board, &Move::new(White, Knight, E4, C5)));
+    }
+
+    #[test]
+    fn gives_check_castle() {
+        let board = Board::from_fen("5k2/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+
+        assert!(gives_check(&board, &Move::new_castle(White, E1, G1)));
+    }
+
+    #[test]
+    fn gives_check_knight_promotion() {
+        let board = Board::from_fen("8/3P1k2/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert!(gives_check(&board, &Move::new_prom(White, D7, D8, Knight)));
+        assert!(!gives_check(&board, &Move::new_prom(White, D7, D8, Queen)));
+    }
+
+    #[test]
+    fn to_san_quiet_moves() {
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(to_san(&board, &Move::new_dbl_push(White, E2, E4)), "e4");
+        assert_eq!(to_san(&board, &Move::new(White, Pawn, E2, E3)), "e3");
+        assert_eq!(to_san(&board, &Move::new(White, Knight, G1, F3)), "Nf3");
+    }
+
+    #[test]
+    fn to_san_captures() {
+        let board =
+            Board::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/2N5/PPPP1PPP/R1BQKBNR w KQkq - 0 2")
+                .unwrap();
+
+        assert_eq!(to_san(&board, &Move::new(White, Pawn, E4, D5)), "exd5");
+        assert_eq!(to_san(&board, &Move::new(White, Knight, C3, D5)), "Nxd5");
+    }
+
+    #[test]
+    fn to_san_en_passant() {
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+        assert_eq!(to_san(&board, &Move::new_en_pass(White, E5, D6)), "exd6");
+    }
+
+    #[test]
+    fn to_san_castle() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        assert_eq!(to_san(&board, &Move::new_castle(White, E1, G1)), "O-O");
+        assert_eq!(to_san(&board, &Move::new_castle(White, E1, C1)), "O-O-O");
+    }
+
+    #[test]
+    fn to_san_disambiguation() {
+        let board = Board::from_fen("1n2k3/8/5n2/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(to_san(&board, &Move::new(Black, Knight, B8, D7)), "Nbd7");
+
+        let board = Board::from_fen("1n2k3/8/1n6/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(to_san(&board, &Move::new(Black, Knight, B8, D7)), "N8d7");
+
+        let board = Board::from_fen("1k6/8/8/8/Q6Q/8/8/4K2Q w - - 0 1").unwrap();
+        assert_eq!(to_san(&board, &Move::new(White, Queen, H4, E4)), "Qh4e4");
+    }
+
+    #[test]
+    fn to_san_no_disambiguation_for_pinned_piece() {
+        let board = Board::from_fen("4k3/8/8/3N4/8/8/8/r2NK3 w - - 0 1").unwrap();
+
+        assert_eq!(to_san(&board, &Move::new(White, Knight, D5, E3)), "Ne3");
+    }
+
+    #[test]
+    fn to_san_capturing_promotion_with_check() {
+        let board = Board::from_fen("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            to_san(&board, &Move::new_prom(White, B7, A8, Queen)),
+            "bxa8=Q+"
+        );
+        assert_eq!(
+            to_san(&board, &Move::new_prom(White, B7, B8, Knight)),
+            "b8=N"
+        );
+    }
+
+    #[test]
+    fn to_san_checkmate() {
+        let board =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2")
+                .unwrap();
+
+        assert_eq!(to_san(&board, &Move::new(Black, Queen, D8, H4)), "Qh4#");
+    }
+
+    #[test]
+    fn from_san_short_game() {
+        let mut board = Board::new_with_standard_formation();
+
+        for (san, exp) in [
+            ("e4", Move::new_dbl_push(White, E2, E4)),
+            ("e5", Move::new_dbl_push(Black, E7, E5)),
+            ("Bc4", Move::new(White, Bishop, F1, C4)),
+            ("Nc6", Move::new(Black, Knight, B8, C6)),
+            ("Qh5", Move::new(White, Queen, D1, H5)),
+            ("Nf6??", Move::new(Black, Knight, G8, F6)),
+            ("Qxf7#", Move::new(White, Queen, H5, F7)),
+        ] {
+            let mv = from_san(&board, san).unwrap();
+            assert_eq!(mv, exp, "{}", san);
+
+            board.do_move(mv);
+        }
+
+        assert!(board.is_checkmate());
+    }
+
+    #[test]
+    fn from_san_castle() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        assert_eq!(
+            from_san(&board, "O-O").unwrap(),
+            Move::new_castle(White, E1, G1)
+        );
+        assert_eq!(
+            from_san(&board, "O-O-O").unwrap(),
+            Move::new_castle(White, E1, C1)
+        );
+    }
+
+    #[test]
+    fn from_san_capture() {
+        let board = Board::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2")
+            .unwrap();
+
+        assert_eq!(
+            from_san(&board, "exd5").unwrap(),
+            Move::new(White, Pawn, E4, D5)
+        );
+    }
+
+    #[test]
+    fn from_san_disambiguation() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1").unwrap();
+
+        assert_eq!(
+            from_san(&board, "Rad1").unwrap(),
+            Move::new(White, Rook, A1, D1)
+        );
+        assert_eq!(
+            from_san(&board, "Rfd1").unwrap(),
+            Move::new(White, Rook, F1, D1)
+        );
+        assert!(from_san(&board, "Rd1").is_err());
+    }
+
+    #[test]
+    fn from_san_promotion() {
+        let board = Board::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            from_san(&board, "a8=Q").unwrap(),
+            Move::new_prom(White, A7, A8, Queen)
+        );
+        assert_eq!(
+            from_san(&board, "axb8=N+").unwrap(),
+            Move::new_prom(White, A7, B8, Knight)
+        );
+    }
+
+    #[test]
+    fn from_san_errors() {
+        let board = Board::new_with_standard_formation();
+
+        for san in ["e5", "Nf4", "O-O", "", "Z", "Nz9", "e"] {
+            assert!(from_san(&board, san).is_err(), "{}", san);
+        }
+    }
+
+    #[test]
+    fn move_src_and_dst_square() {
+        let mv = Move::new(White, Knight, G1, F3);
+
+        assert_eq!(mv.src_square(), G1);
+        assert_eq!(mv.dst_square(), F3);
+        assert_eq!(mv.src_square(), Square::try_from(mv.src()).unwrap());
+        assert_eq!(mv.dst_square(), Square::try_from(mv.dst()).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn move_serde_round_trip() {
+        for mv in [
+            Move::new(White, Knight, G1, F3),
+            Move::new_castle(Black, E8, C8),
+            Move::new_prom(White, B7, A8, Queen),
+        ] {
+            let json = serde_json::to_string(&mv).unwrap();
+
+            assert_eq!(serde_json::from_str::<Move>(&json).unwrap(), mv);
+        }
+
+        let json = serde_json::to_string(&E4).unwrap();
+
+        assert_eq!(serde_json::from_str::<Square>(&json).unwrap(), E4);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn move_serde_rejects_invalid() {
+        let valid = serde_json::to_value(Move::new_prom(White, B7, A8, Queen)).unwrap();
+
+        for (field, value) in [
+            ("dst", serde_json::json!(64)),
+            ("src", serde_json::json!(usize::from(A8))),
+            ("is_castle", serde_json::json!(true)),
+            ("prom_to", serde_json::json!("King")),
+            ("piece", serde_json::json!("Rook")),
+        ] {
+            let mut json = valid.clone();
+            json[field] = value;
+
+            assert!(serde_json::from_value::<Move>(json).is_err(), "{}", field);
+        }
+
+        let mut json = serde_json::to_value(Move::new_en_pass(White, A5, B6)).unwrap();
+        json["is_capture"] = serde_json::json!(false);
+
+        assert!(serde_json::from_value::<Move>(json).is_err());
+        assert!(serde_json::from_value::<Move>(valid).is_ok());
+    }
+
+    #[test]
+    fn to_uci_normal_and_promotion() {
+        assert_eq!(to_uci(&Move::new_dbl_push(White, E2, E4)), "e2e4");
+        assert_eq!(to_uci(&Move::new_prom(Black, B2, A1, Knight)), "b2a1n");
+    }
+
+    #[test]
+    fn from_uci_castle() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        assert_eq!(
+            from_uci(&board, "e1g1").unwrap(),
+            Move::new_castle(White, E1, G1)
+        );
+    }
+
+    #[test]
+    fn from_uci_promotion() {
+        let board = Board::from_fen("7k/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            from_uci(&board, "e7e8q").unwrap(),
+            Move::new_prom(White, E7, E8, Queen)
+        );
+    }
+
+    #[test]
+    fn from_uci_en_passant() {
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+        assert_eq!(
+            from_uci(&board, "e5d6").unwrap(),
+            Move::new_en_pass(White, E5, D6)
+        );
+    }
+
+    #[test]
+    fn from_uci_errors() {
+        let board = Board::from_fen("7k/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        for uci in ["e7e", "e7e8qq", "e7e8k", "e7e8x", "z7e8", "e1e3", "e7e8"] {
+            assert!(from_uci(&board, uci).is_err(), "{}", uci);
+        }
+    }
+
+    #[test]
+    fn perft_starting_position() {
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(perft(&board, 0), 1);
+        assert_eq!(perft(&board, 1), 20);
+        assert_eq!(perft(&board, 2), 400);
+        assert_eq!(perft(&board, 3), 8_902);
+    }
+
+    #[test]
+    fn perft_parallel_matches_perft() {
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(perft_parallel(&board, 4), perft(&board, 4));
+        assert_eq!(perft_parallel(&board, 1), 20);
+        assert_eq!(perft_parallel(&board, 0), 1);
+    }
+
+    #[test]
+    fn perft_divide_starting_position() {
+        let board = Board::new_with_standard_formation();
+
+        let divide = perft_divide(&board, 2);
+        assert_eq!(divide.len(), 20);
+        assert!(divide.iter().all(|(_, nodes)| *nodes == 20));
+
+        let divide: Vec<_> = perft_divide(&board, 3)
+            .into_iter()
+            .map(|(mv, nodes)| (to_uci(&mv), nodes))
+            .collect();
+        let exp: Vec<_> = [
+            ("a2a3", 380),
+            ("a2a4", 420),
+            ("b1a3", 400),
+            ("b1c3", 440),
+            ("b2b3", 420),
+            ("b2b4", 421),
+            ("c2c3", 420),
+            ("c2c4", 441),
+            ("d2d3", 539),
+            ("d2d4", 560),
+            ("e2e3", 599),
+            ("e2e4", 600),
+            ("f2f3", 380),
+            ("f2f4", 401),
+            ("g1f3", 440),
+            ("g1h3", 400),
+            ("g2g3", 420),
+            ("g2g4", 421),
+            ("h2h3", 380),
+            ("h2h4", 420),
+        ]
+        .into_iter()
+        .map(|(uci, nodes)| (uci.to_owned(), nodes))
+        .collect();
+
+        assert_eq!(divide, exp);
+    }
+
+    #[test]
+    fn perft_detailed_kiwipete() {
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+
+        assert_eq!(
+            perft_detailed(&board, 1),
+            PerftStats {
+                captures: 8,
+                castles: 2,
+                checkmates: 0,
+                checks: 0,
+                en_passants: 0,
+                nodes: 48,
+                promotions: 0,
+            }
+        );
+        assert_eq!(
+            perft_detailed(&board, 2),
+            PerftStats {
+                captures: 351,
+                castles: 91,
+                checkmates: 0,
+                checks: 3,
+                en_passants: 1,
+                nodes: 2_039,
+                promotions: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn perft_detailed_checkmates() {
+        // Position 3 of the chessprogramming.org perft results.
+        let board = Board::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+
+        assert_eq!(
+            perft_detailed(&board, 4),
+            PerftStats {
+                captures: 3_348,
+                castles: 0,
+                checkmates: 17,
+                checks: 1_680,
+                en_passants: 123,
+                nodes: 43_238,
+                promotions: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn moves_from_central_knight() {
+        let board = Board::from_fen("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_moves_eq(
+            &moves_from(&board, D4),
+            &[
+                Move::new(White, Knight, D4, B3),
+                Move::new(White, Knight, D4, B5),
+                Move::new(White, Knight, D4, C2),
+                Move::new(White, Knight, D4, C6),
+                Move::new(White, Knight, D4, E2),
+                Move::new(White, Knight, D4, E6),
+                Move::new(White, Knight, D4, F3),
+                Move::new(White, Knight, D4, F5),
+            ],
+        );
+    }
+
+    #[test]
+    fn moves_from_pinned_piece() {
+        let board = Board::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+
+        assert_moves_eq(&moves_from(&board, E2), &[]);
+    }
+
+    #[test]
+    fn moves_from_empty_or_opponent_square() {
+        let board = Board::new_with_standard_formation();
+
+        assert_moves_eq(&moves_from(&board, E4), &[]);
+        assert_moves_eq(&moves_from(&board, E7), &[]);
+    }
+
+    #[test]
+    fn moves_from_matches_legal_moves() {
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+
+        let all: Vec<_> = (0..Board::SIZE)
+            .flat_map(|i| moves_from(&board, i))
+            .collect();
+
+        assert_moves_eq(&all, &legal_moves(&board));
+    }
+
+    #[test]
+    fn fill_moves_reuses_buffer() {
+        let mut moves = Vec::new();
+
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        fill_moves(&board, &mut moves);
+        assert_moves_eq(&moves, &all_moves(&board));
+
+        let board = Board::new_with_standard_formation();
+        fill_moves(&board, &mut moves);
+        assert_moves_eq(&moves, &all_moves(&board));
+    }
+
+    #[test]
+    fn capture_moves_match_filtered_all_moves() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+            let opp_occ = board.occupied_by(board.side_to_move().opposing());
+
+            let exp_moves: Vec<_> = all_moves(&board)
+                .into_iter()
+                .filter(|mv| mv.is_en_passant() || bit_board::is_bit_set(opp_occ, mv.dst()))
+                .collect();
+
+            assert_moves_eq(&capture_moves(&board), &exp_moves);
+        }
+    }
+
+    #[test]
+    fn capture_moves_promotion_captures_all_pieces() {
+        let board = Board::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_moves_eq(
+            &capture_moves(&board),
+            &[
+                Move::new_prom(White, A7, B8, Bishop),
+                Move::new_prom(White, A7, B8, Knight),
+                Move::new_prom(White, A7, B8, Queen),
+                Move::new_prom(White, A7, B8, Rook),
+            ],
+        );
+    }
+
+    fn assert_moves_eq(left: &[Move], right: &[Move]) {
+        let mut left = left.to_vec();
+        left.sort_by(display_value);
+        let mut right = right.to_vec();
+        right.sort_by(display_value);
+
+        assert_eq!(
+            left,
+            right,
+            "\nexpected:\n\t{}\nto equal:\n\t{}\n",
+            display(&left),
+            display(&right),
+        );
+
+        fn display(moves: &[Move]) -> String {
+            let display_val = moves
+                .iter()
+                .map(|m| format!("{}", m))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            if display_val.chars().count() == 0 {
+                return "<no moves>".to_owned();
+            }
+
+            display_val
+        }
+
+        fn display_value(a: &Move, b: &Move) -> Ordering {
+            format!("{}", a).cmp(&format!("{}", b))
+        }
+    }
+}
+
+/// The leaf nodes of [`perft_detailed`], classified by the move leading to them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PerftStats {
+    pub captures: u64,
+    pub castles: u64,
+    pub checkmates: u64,
+    pub checks: u64,
+    pub en_passants: u64,
+    pub nodes: u64,
+    pub promotions: u64,
+}
+
+// The kinds of moves encoded in the upper 4 bits of a packed move, see
+// `Move::to_packed`.
+const PACKED_QUIET: u16 = 0;
+const PACKED_DBL_PUSH: u16 = 1;
+const PACKED_CASTLE: u16 = 2;
+const PACKED_EN_PASSANT: u16 = 3;
+const PACKED_PROM_BISHOP: u16 = 4;
+const PACKED_PROM_KNIGHT: u16 = 5;
+const PACKED_PROM_QUEEN: u16 = 6;
+const PACKED_PROM_ROOK: u16 = 7;
+
+#[derive(Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawMove"))]
 pub struct Move {
     dst: usize,
+    is_capture: bool,
     is_castle: bool,
     is_dbl_push: bool,
     is_en_passant: bool,
@@ -1300,10 +2747,74 @@ pub struct Move {
 }
 
 impl Move {
+    /// Same as [`Move::new`], but only accepting [`Square`]s, which can't be
+    /// mixed up with other indexes.
+    ///
+    /// ```
+    /// use chess_logic::{move_generator::Move, Color::White, Piece::Knight, Square};
+    ///
+    /// let mv = Move::between(White, Knight, Square::G1, Square::F3);
+    ///
+    /// assert_eq!(mv.src_square(), Square::G1);
+    /// assert_eq!(mv.dst_square(), Square::F3);
+    /// assert_eq!(mv, Move::new(White, Knight, Square::G1, Square::F3));
+    /// ```
+    pub fn between(color: Color, piece: Piece, src: Square, dst: Square) -> Self {
+        Self::new(color, piece, src, dst)
+    }
+
     pub fn dst(&self) -> usize {
         self.dst
     }
 
+    /// Same as [`Move::dst`], but as a [`Square`].
+    pub fn dst_square(&self) -> Square {
+        Square::ALL[self.dst]
+    }
+
+    /// Reconstructs a move encoded with [`Move::to_packed`].
+    ///
+    /// The piece and its color are taken from the source square, so `board` has
+    /// to be the position the move was generated for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source square is empty, or the flags are invalid.
+    pub fn from_packed(packed: u16, board: &Board) -> Move {
+        let src = (packed & 0x3f) as usize;
+        let dst = ((packed >> 6) & 0x3f) as usize;
+        let ins = board
+            .get(src)
+            .unwrap_or_else(|| panic!("no piece to move on '{:?}'", Square::ALL[src]));
+
+        let mut mv = match packed >> 12 {
+            PACKED_QUIET => Move::new(ins.color, ins.piece, src, dst),
+            PACKED_DBL_PUSH => Move::new_dbl_push(ins.color, src, dst),
+            PACKED_CASTLE => Move::new_castle(ins.color, src, dst),
+            PACKED_EN_PASSANT => Move::new_en_pass(ins.color, src, dst),
+            PACKED_PROM_BISHOP => Move::new_prom(ins.color, src, dst, Bishop),
+            PACKED_PROM_KNIGHT => Move::new_prom(ins.color, src, dst, Knight),
+            PACKED_PROM_QUEEN => Move::new_prom(ins.color, src, dst, Queen),
+            PACKED_PROM_ROOK => Move::new_prom(ins.color, src, dst, Rook),
+            flags => panic!("invalid packed move flags '{}'", flags),
+        };
+
+        if bit_board::is_bit_set(board.occupied_by(ins.color.opposing()), dst) {
+            mv.set_is_capture(true);
+        }
+
+        mv
+    }
+
+    /// Whether the move captures a piece, including en passant captures.
+    ///
+    /// This is set by the move generator, since it depends on the board. Moves
+    /// constructed manually (other than with [`Move::new_en_pass`]) are not
+    /// captures, unless set with [`Move::set_is_capture`].
+    pub fn is_capture(&self) -> bool {
+        self.is_capture
+    }
+
     pub fn is_castle(&self) -> bool {
         self.is_castle
     }
@@ -1319,6 +2830,7 @@ impl Move {
     pub fn new(color: Color, piece: Piece, src: impl BoardPos, dst: impl BoardPos) -> Self {
         Self {
             dst: dst.into(),
+            is_capture: false,
             is_castle: false,
             is_dbl_push: false,
             is_en_passant: false,
@@ -1345,6 +2857,7 @@ impl Move {
 
     pub fn new_en_pass(color: Color, src: impl BoardPos, dst: impl BoardPos) -> Self {
         Self {
+            is_capture: true,
             is_en_passant: true,
             ..Self::new(color, Pawn, src, dst)
         }
@@ -1381,6 +2894,10 @@ impl Move {
         self.prom_to
     }
 
+    pub fn set_is_capture(&mut self, val: bool) {
+        self.is_capture = val;
+    }
+
     pub fn set_is_castle(&mut self, val: bool) {
         self.is_castle = val;
     }
@@ -1416,6 +2933,119 @@ impl Move {
     pub fn src(&self) -> usize {
         self.src
     }
+
+    /// Encodes the move into 16 bits, e.g. for storing it in a transposition table.
+    ///
+    /// The source and destination take 6 bits each, the remaining 4 bits encode
+    /// the kind of move (including the promotion piece). Use [`Move::from_packed`]
+    /// to decode it again.
+    pub fn to_packed(&self) -> u16 {
+        let flags = match (
+            self.prom_to,
+            self.is_castle,
+            self.is_dbl_push,
+            self.is_en_passant,
+        ) {
+            (Some(Bishop), ..) => PACKED_PROM_BISHOP,
+            (Some(Knight), ..) => PACKED_PROM_KNIGHT,
+            (Some(Queen), ..) => PACKED_PROM_QUEEN,
+            (Some(Rook), ..) => PACKED_PROM_ROOK,
+            (Some(prom_to), ..) => panic!("invalid promotion to '{:?}'", prom_to),
+            (None, true, ..) => PACKED_CASTLE,
+            (None, _, true, _) => PACKED_DBL_PUSH,
+            (None, _, _, true) => PACKED_EN_PASSANT,
+            (None, false, false, false) => PACKED_QUIET,
+        };
+
+        self.src as u16 | (self.dst as u16) << 6 | flags << 12
+    }
+
+    /// Same as [`Move::src`], but as a [`Square`].
+    pub fn src_square(&self) -> Square {
+        Square::ALL[self.src]
+    }
+}
+
+/// The unchecked fields of a deserialized [`Move`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawMove {
+    dst: usize,
+    is_capture: bool,
+    is_castle: bool,
+    is_dbl_push: bool,
+    is_en_passant: bool,
+    piece: Piece,
+    piece_color: Color,
+    prom_to: Option<Piece>,
+    src: usize,
+}
+
+/// Rejects moves the constructors of [`Move`] can't create, so that deserialized
+/// moves can't index out of the board or carry contradicting flags.
+#[cfg(feature = "serde")]
+impl TryFrom<RawMove> for Move {
+    type Error = String;
+
+    fn try_from(raw: RawMove) -> Result<Self, Self::Error> {
+        if raw.src >= 64 || raw.dst >= 64 || raw.src == raw.dst {
+            return Err(format!("invalid squares '{}' -> '{}'", raw.src, raw.dst));
+        }
+
+        let kinds = [
+            raw.is_castle,
+            raw.is_dbl_push,
+            raw.is_en_passant,
+            raw.prom_to.is_some(),
+        ];
+
+        if kinds.into_iter().filter(|&kind| kind).count() > 1 {
+            return Err("move has more than one special kind".to_string());
+        }
+
+        if raw.is_castle && raw.piece != King {
+            return Err(format!("'{:?}' can't castle", raw.piece));
+        }
+
+        if (raw.is_dbl_push || raw.is_en_passant || raw.prom_to.is_some()) && raw.piece != Pawn {
+            return Err(format!("'{:?}' can't move like a pawn", raw.piece));
+        }
+
+        if raw.is_en_passant && !raw.is_capture {
+            return Err("en passant move is not a capture".to_string());
+        }
+
+        if matches!(raw.prom_to, Some(King | Pawn)) {
+            return Err(format!("can't promote to '{:?}'", raw.prom_to.unwrap()));
+        }
+
+        Ok(Self {
+            dst: raw.dst,
+            is_capture: raw.is_capture,
+            is_castle: raw.is_castle,
+            is_dbl_push: raw.is_dbl_push,
+            is_en_passant: raw.is_en_passant,
+            piece: raw.piece,
+            piece_color: raw.piece_color,
+            prom_to: raw.prom_to,
+            src: raw.src,
+        })
+    }
+}
+
+/// Whether the move is a capture is ignored, as it is derived from the board the
+/// move was generated for.
+impl PartialEq for Move {
+    fn eq(&self, other: &Self) -> bool {
+        self.dst == other.dst
+            && self.is_castle == other.is_castle
+            && self.is_dbl_push == other.is_dbl_push
+            && self.is_en_passant == other.is_en_passant
+            && self.piece == other.piece
+            && self.piece_color == other.piece_color
+            && self.prom_to == other.prom_to
+            && self.src == other.src
+    }
 }
 
 impl Display for Move {
@@ -1425,10 +3055,14 @@ impl Display for Move {
             "{:?} {:?}: {:?}->{:?}",
             self.piece_color,
             self.piece,
-            Square::try_from(self.src()).unwrap(),
-            Square::try_from(self.dst()).unwrap(),
+            self.src_square(),
+            self.dst_square(),
         )?;
 
+        if self.is_capture() {
+            write!(f, " (capture)")?;
+        }
+
         if self.is_castle() {
             write!(f, " (castle)")?;
         }
@@ -0,0 +1,179 @@
+use crate::{
+    board::GameResult,
+    move_generator::{self, Move},
+    Board, Color,
+};
+
+/// Decodes PGN movetext into the moves played from `start`.
+///
+/// Move numbers, comments (`{...}`), and the result are skipped, as are tag pairs
+/// (e.g. `[Event "..."]`), which are not interpreted. Every move is resolved
+/// against the position it is played in, using [`move_generator::from_san`].
+pub fn from_pgn_movetext(start: &Board, pgn: &str) -> Result<Vec<Move>, String> {
+    let mut board = start.clone();
+    let mut moves = Vec::new();
+
+    let movetext = pgn
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('['))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut without_comments = String::new();
+    let mut in_comment = false;
+
+    for c in movetext.chars() {
+        match c {
+            // Comments are replaced by a space, to keep the tokens around them apart.
+            '{' => {
+                in_comment = true;
+                without_comments.push(' ');
+            }
+            '}' if in_comment => {
+                in_comment = false;
+                without_comments.push(' ');
+            }
+            _ if in_comment => {}
+            _ => without_comments.push(c),
+        }
+    }
+
+    if in_comment {
+        return Err("unterminated comment".to_owned());
+    }
+
+    for token in without_comments.split_whitespace() {
+        if matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*") {
+            continue;
+        }
+
+        // Move numbers may be directly followed by the move, e.g. `1.e4`.
+        let san = token
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .trim_start_matches('.');
+
+        if san.is_empty() {
+            continue;
+        }
+
+        let mv = move_generator::from_san(&board, san)
+            .map_err(|err| format!("failed to resolve move '{}': {}", token, err))?;
+
+        board.do_move(mv.clone());
+        moves.push(mv);
+    }
+
+    Ok(moves)
+}
+
+/// Encodes the moves played from `start` as PGN movetext.
+///
+/// The moves are written in SAN and numbered, e.g. `1. e4 e5 2. Nf3`. If the
+/// final position is terminal (see [`Board::result`]), the result is appended.
+/// The moves are expected to be legal, see [`Board::try_do_move`].
+pub fn to_pgn_movetext(start: &Board, moves: &[Move]) -> String {
+    let mut board = start.clone();
+    let mut tokens = Vec::new();
+
+    for (i, mv) in moves.iter().enumerate() {
+        if board.side_to_move() == Color::White {
+            tokens.push(format!("{}.", board.full_move_number));
+        } else if i == 0 {
+            tokens.push(format!("{}...", board.full_move_number));
+        }
+
+        tokens.push(move_generator::to_san(&board, mv));
+        board.do_move(mv.clone());
+    }
+
+    match board.result() {
+        GameResult::BlackWins => tokens.push("0-1".to_owned()),
+        GameResult::Draw => tokens.push("1/2-1/2".to_owned()),
+        GameResult::Ongoing => {}
+        GameResult::WhiteWins => tokens.push("1-0".to_owned()),
+    }
+
+    tokens.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::Fen, Color::*, Piece::*, Square::*};
+
+    use super::*;
+
+    #[test]
+    fn to_pgn_movetext_scholars_mate() {
+        let moves = [
+            Move::new_dbl_push(White, E2, E4),
+            Move::new_dbl_push(Black, E7, E5),
+            Move::new(White, Bishop, F1, C4),
+            Move::new(Black, Knight, B8, C6),
+            Move::new(White, Queen, D1, H5),
+            Move::new(Black, Knight, G8, F6),
+            Move::new(White, Queen, H5, F7),
+        ];
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(
+            to_pgn_movetext(&board, &moves),
+            "1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0"
+        );
+    }
+
+    #[test]
+    fn to_pgn_movetext_black_to_move() {
+        let board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+        let moves = [
+            Move::new_dbl_push(Black, C7, C5),
+            Move::new(White, Knight, G1, F3),
+        ];
+
+        assert_eq!(to_pgn_movetext(&board, &moves), "1... c5 2. Nf3");
+        assert_eq!(to_pgn_movetext(&board, &[]), "");
+    }
+
+    #[test]
+    fn from_pgn_movetext_short_game() {
+        let pgn = r#"[Event "Casual game"]
+[White "?"]
+[Black "?"]
+
+1. e4 e5 2. Nf3 {The most common move.} Nc6 3.Bb5 a6 4. Bxa6 bxa6 5. O-O Nf6 1/2-1/2"#;
+        let board = Board::new_with_standard_formation();
+        let moves = from_pgn_movetext(&board, pgn).unwrap();
+
+        assert_eq!(moves.len(), 10);
+
+        let mut end = board.clone();
+
+        for mv in &moves {
+            assert!(end.try_do_move(mv.clone()).is_ok());
+        }
+
+        assert_eq!(
+            end.get_fen(),
+            "r1bqkb1r/2pp1ppp/p1n2n2/4p3/4P3/5N2/PPPP1PPP/RNBQ1RK1 w kq - 2 6"
+        );
+    }
+
+    #[test]
+    fn from_pgn_movetext_round_trip() {
+        let board = Board::new_with_standard_formation();
+        let movetext = "1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0";
+        let moves = from_pgn_movetext(&board, movetext).unwrap();
+
+        assert_eq!(to_pgn_movetext(&board, &moves), movetext);
+    }
+
+    #[test]
+    fn from_pgn_movetext_invalid() {
+        let board = Board::new_with_standard_formation();
+
+        assert!(from_pgn_movetext(&board, "1. e4 e5 2. Ke3")
+            .unwrap_err()
+            .contains("'Ke3'"));
+        assert!(from_pgn_movetext(&board, "1. e4 {unterminated").is_err());
+    }
+}
@@ -3,17 +3,15 @@ use std::{cmp::min, ops::Range};
 use once_cell::sync::Lazy;
 
 use crate::{
-    bit_board::{self, ColoredU64PerSquare, U64PerSquare},
+    bit_board::{self, ColoredU64PerSquare, U64PerSquare, NOT_FILE_A, NOT_FILE_H},
     board::BoardPos,
     magic_bit_board,
     type_alias_default::TypeAliasDefault,
     Board, Color,
 };
 
-const NOT_FILE_A: u64 = 18374403900871474942;
 const NOT_FILE_AB: u64 = 18229723555195321596;
 const NOT_FILE_GH: u64 = 4557430888798830399;
-const NOT_FILE_H: u64 = 9187201950435737471;
 
 static KING_ATTACK_MASK: Lazy<U64PerSquare> = Lazy::new(generate_king_attacks);
 static KNIGHT_ATTACK_MASK: Lazy<U64PerSquare> = Lazy::new(generate_knight_attacks);
@@ -52,17 +50,19 @@ pub fn calculate_bishop_attacks_for(pos: impl Into<usize>, blockers: u64) -> u64
     calculate_sliding_attacks_for(pos, blockers, |file, rank| {
         [
             // moves to north east
-            (1..min(Board::WIDTH - file, rank + 1), |b, i| b >> NO_EA * i),
+            (1..min(Board::WIDTH - file, rank + 1), |b, i| {
+                b >> (NO_EA * i)
+            }),
             // moves to south east
             (1..min(Board::WIDTH - file, Board::HEIGHT - rank), |b, i| {
-                b << SO_EA * i
+                b << (SO_EA * i)
             }),
             // moves to south west
             (1..min(file + 1, Board::HEIGHT - rank), |b, i| {
-                b << SO_WE * i
+                b << (SO_WE * i)
             }),
             // moves to north west
-            (1..(min(file, rank) + 1), |b, i| b >> NO_WE * i),
+            (1..(min(file, rank) + 1), |b, i| b >> (NO_WE * i)),
         ]
     })
 }
@@ -73,17 +73,20 @@ pub fn calculate_rook_attacks_for(pos: impl Into<usize>, blockers: u64) -> u64 {
     calculate_sliding_attacks_for(pos, blockers, |file, rank| {
         [
             // moves to north
-            (1..rank + 1, |b, i| b >> NORTH * i),
+            (1..rank + 1, |b, i| b >> (NORTH * i)),
             // moves to east
-            (1..Board::WIDTH - file, |b, i| b << EAST * i),
+            (1..Board::WIDTH - file, |b, i| b << (EAST * i)),
             // moves to south
-            (1..Board::HEIGHT - rank, |b, i| b << SOUTH * i),
+            (1..Board::HEIGHT - rank, |b, i| b << (SOUTH * i)),
             // moves to west
-            (1..file + 1, |b, i| b >> WEST * i),
+            (1..file + 1, |b, i| b >> (WEST * i)),
         ]
     })
 }
 
+/// Ranges to iterate over, paired with the shift for the respective direction.
+type SlidingDirs = [(Range<usize>, fn(u64, usize) -> u64); 4];
+
 /// Calculates the moves of sliding pieces.
 ///
 /// # Arguments
@@ -91,11 +94,11 @@ pub fn calculate_rook_attacks_for(pos: impl Into<usize>, blockers: u64) -> u64 {
 /// * `pos` - the position of the pice
 /// * `blockers` - other pieces on the board (they could block moves)
 /// * `get_dirs` - closure that returns range to loop over, and the offset for
-///    the desired direction
+///   the desired direction
 fn calculate_sliding_attacks_for(
     pos: impl Into<usize>,
     blockers: u64,
-    get_dirs: fn(usize, usize) -> [(Range<usize>, fn(u64, usize) -> u64); 4],
+    get_dirs: fn(usize, usize) -> SlidingDirs,
 ) -> u64 {
     let i = pos.into();
 
@@ -227,9 +230,31 @@ mod tests {
             1157443723186929664,
         );
     }
+
+    #[test]
+    fn char_round_trip() {
+        for (piece, c) in [
+            (Piece::Bishop, 'B'),
+            (Piece::King, 'K'),
+            (Piece::Knight, 'N'),
+            (Piece::Pawn, 'P'),
+            (Piece::Queen, 'Q'),
+            (Piece::Rook, 'R'),
+        ] {
+            assert_eq!(piece.to_char(), c);
+            assert_eq!(Piece::from_char(c), Some(piece));
+            assert_eq!(Piece::from_char(c.to_ascii_lowercase()), Some(piece));
+        }
+    }
+
+    #[test]
+    fn from_char_invalid() {
+        assert_eq!(Piece::from_char('x'), None);
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Piece {
     Bishop,
     King,
@@ -240,6 +265,39 @@ pub enum Piece {
 }
 
 impl Piece {
+    /// All pieces, in the order of their discriminants.
+    ///
+    /// ```
+    /// use chess_logic::Piece;
+    ///
+    /// for piece in Piece::all() {
+    ///     assert_eq!(Piece::from_char(piece.to_char()), Some(piece));
+    /// }
+    /// ```
+    pub const fn all() -> [Piece; 6] {
+        [
+            Piece::Bishop,
+            Piece::King,
+            Piece::Knight,
+            Piece::Pawn,
+            Piece::Queen,
+            Piece::Rook,
+        ]
+    }
+
+    /// Parses the piece from its letter, regardless of case (e.g. `'n'` or `'N'`).
+    pub fn from_char(c: char) -> Option<Piece> {
+        match c.to_ascii_uppercase() {
+            'B' => Some(Piece::Bishop),
+            'K' => Some(Piece::King),
+            'N' => Some(Piece::Knight),
+            'P' => Some(Piece::Pawn),
+            'Q' => Some(Piece::Queen),
+            'R' => Some(Piece::Rook),
+            _ => None,
+        }
+    }
+
     /// Returns the symbol in unicode.
     ///
     /// https://en.wikipedia.org/wiki/Chess_symbols_in_Unicode
@@ -259,31 +317,41 @@ impl Piece {
             (Color::White, Piece::Rook) => "♜",
         }
     }
+
+    /// Returns the uppercase letter of the piece (e.g. `'N'` for the knight).
+    pub fn to_char(self) -> char {
+        match self {
+            Piece::Bishop => 'B',
+            Piece::King => 'K',
+            Piece::Knight => 'N',
+            Piece::Pawn => 'P',
+            Piece::Queen => 'Q',
+            Piece::Rook => 'R',
+        }
+    }
 }
 
+#[allow(clippy::needless_range_loop)]
 fn generate_king_attacks() -> U64PerSquare {
     let mut mask = U64PerSquare::default();
 
     for i in 0..Board::SIZE {
         let board = bit_board::with_bit_at(i);
 
-        mask[i] |= board >> bit_board::NORTH;
-        if bit_board::is_bit_set(board & NOT_FILE_H, i) {
-            mask[i] |= board >> bit_board::NO_EA;
-            mask[i] |= board << bit_board::EAST;
-            mask[i] |= board << bit_board::SO_EA;
-        }
-        mask[i] |= board << bit_board::SOUTH;
-        if bit_board::is_bit_set(board & NOT_FILE_A, i) {
-            mask[i] |= board << bit_board::SO_WE;
-            mask[i] |= board >> bit_board::WEST;
-            mask[i] |= board >> bit_board::NO_WE;
-        }
+        mask[i] = bit_board::shift_north(board)
+            | bit_board::shift_no_ea(board)
+            | bit_board::shift_east(board)
+            | bit_board::shift_so_ea(board)
+            | bit_board::shift_south(board)
+            | bit_board::shift_so_we(board)
+            | bit_board::shift_west(board)
+            | bit_board::shift_no_we(board);
     }
 
     mask
 }
 
+#[allow(clippy::needless_range_loop)]
 fn generate_knight_attacks() -> U64PerSquare {
     let mut mask = U64PerSquare::default();
 
@@ -315,6 +383,7 @@ fn generate_knight_attacks() -> U64PerSquare {
     mask
 }
 
+#[allow(clippy::needless_range_loop)]
 fn generate_pawn_attacks() -> ColoredU64PerSquare {
     let mut mask = ColoredU64PerSquare::default();
 
@@ -0,0 +1,621 @@
+use std::{
+    cmp::Reverse,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    evaluation::{self, MAT_VAL},
+    move_generator::{self, Move},
+    Board, Piece,
+};
+
+/// The score of a checkmated side, before it's scaled by the remaining depth.
+const MATE_SCORE: i32 = 1_000_000;
+
+/// Bound larger than any score. Unlike `i32::MIN`, it can be safely negated.
+const INFINITY: i32 = i32::MAX;
+
+/// The deepest iteration of [`search_timed`].
+const MAX_DEPTH: usize = 64;
+
+/// Finds the best move for the side to move, searching `depth` plies deep.
+///
+/// Uses the negamax algorithm, where the score is always relative to the side to
+/// move. Returns `None` if there are no legal moves.
+pub fn best_move(board: &Board, depth: usize) -> Option<Move> {
+    search(board, depth).map(|(_, mv)| mv)
+}
+
+/// Same as [`best_move`], but prunes subtrees using alpha-beta bounds.
+///
+/// The resulting score is identical to the one of [`best_move`], but far fewer
+/// positions have to be visited.
+pub fn best_move_ab(board: &Board, depth: usize) -> Option<Move> {
+    search_ab(board, depth).map(|(_, mv)| mv)
+}
+
+/// Same as [`best_move_ab`], but reuses the results of positions already searched
+/// through the given [`TranspositionTable`].
+///
+/// The table may be reused for multiple searches, e.g. the moves of a game.
+pub fn best_move_tt(board: &Board, depth: usize, tt: &mut TranspositionTable) -> Option<Move> {
+    search_tt(board, depth, tt).map(|(_, mv)| mv)
+}
+
+/// Orders the moves, so that the most promising ones are searched first.
+///
+/// Captures come first, ordered by MVV-LVA (most valuable victim, least valuable
+/// attacker). Quiet moves are kept in their original order after the captures.
+pub fn order_moves(board: &Board, moves: &mut [Move]) {
+    moves.sort_by_cached_key(|mv| {
+        let victim = match mv.is_en_passant() {
+            true => Some(Piece::Pawn),
+            false => board
+                .get(mv.dst())
+                .filter(|ins| ins.color != mv.piece_color())
+                .map(|ins| ins.piece),
+        };
+
+        Reverse(victim.map(|victim| MAT_VAL[victim] as i32 - MAT_VAL[mv.piece()] as i32))
+    });
+}
+
+/// Finds the best move within the given time budget, using iterative deepening.
+///
+/// Alpha-beta searches (see [`best_move_ab`]) are run at increasing depths, until
+/// the time runs out. The best move and score (relative to the side to move) of
+/// the last completed iteration are returned. The clock is checked between the
+/// moves at the root, so the budget may be exceeded by the time it takes to search
+/// a single root move. The first iteration is always completed. Returns `None` if
+/// there are no legal moves.
+pub fn search_timed(board: &Board, max_millis: u64) -> Option<(Move, i32)> {
+    let deadline = Instant::now() + Duration::from_millis(max_millis);
+    let mut moves = move_generator::legal_moves(board);
+    let mut best = None;
+
+    order_moves(board, &mut moves);
+
+    for depth in 1..=MAX_DEPTH {
+        let iteration_deadline = best.as_ref().map(|_| deadline);
+
+        let Some((score, mv)) = search_ab_until(board, depth, &moves, iteration_deadline, None)
+        else {
+            break;
+        };
+
+        // The best move is searched first in the next iteration, which results in
+        // more cutoffs.
+        moves.retain(|other| *other != mv);
+        moves.insert(0, mv.clone());
+        best = Some((mv, score));
+
+        // There is no need to search any deeper, once a mate is found.
+        if score.abs() >= MATE_SCORE || Instant::now() >= deadline {
+            break;
+        }
+    }
+
+    best
+}
+
+/// Searches captures until the position is quiet, to avoid the horizon effect.
+///
+/// The side to move may "stand pat" on the static evaluation, since it isn't forced
+/// to capture. Every capture strictly reduces the material, so the search always
+/// terminates.
+pub fn quiescence(board: &mut Board, mut alpha: i32, beta: i32) -> i32 {
+    let stand_pat = evaluation::evaluate_relative(board);
+
+    if stand_pat >= beta {
+        return beta;
+    }
+
+    alpha = alpha.max(stand_pat);
+
+    let mut captures = move_generator::capture_moves(board);
+    order_moves(board, &mut captures);
+
+    for mv in captures {
+        let undo = board.make_move(mv.clone());
+
+        // The captures are only pseudo-legal.
+        if board.leaves_king_in_check(mv.piece_color()) {
+            board.unmake_move(mv, undo);
+            continue;
+        }
+
+        let score = -quiescence(board, -beta, -alpha);
+        board.unmake_move(mv, undo);
+
+        if score >= beta {
+            return beta;
+        }
+
+        alpha = alpha.max(score);
+    }
+
+    alpha
+}
+
+/// Scores a position without legal moves, or `None` if there are legal moves.
+///
+/// The remaining depth is added to the mate score, so that faster mates are
+/// preferred.
+fn terminal_score(board: &Board, moves: &[Move], depth: usize) -> Option<i32> {
+    if !moves.is_empty() {
+        return None;
+    }
+
+    Some(match board.is_in_check() {
+        true => -(MATE_SCORE + depth as i32),
+        false => 0,
+    })
+}
+
+fn negamax(
+    board: &mut Board,
+    depth: usize,
+    mut alpha: i32,
+    beta: i32,
+    mut tt: Option<&mut TranspositionTable>,
+) -> i32 {
+    let alpha_orig = alpha;
+    let mut tt_move = None;
+
+    if let Some(entry) = tt.as_deref().and_then(|tt| tt.probe(board.hash, depth)) {
+        match entry.bound {
+            Bound::Exact => return entry.score.clamp(alpha, beta),
+            Bound::Lower if entry.score >= beta => return beta,
+            Bound::Upper if entry.score <= alpha => return alpha,
+            _ => tt_move = entry.best_move,
+        }
+    }
+
+    let mut moves = move_generator::legal_moves(board);
+
+    if let Some(score) = terminal_score(board, &moves, depth) {
+        return score;
+    }
+
+    if depth == 0 {
+        return quiescence(board, alpha, beta);
+    }
+
+    order_moves(board, &mut moves);
+
+    // The best move of a previous search is likely to be the best again.
+    if let Some(pos) =
+        tt_move.and_then(|packed| moves.iter().position(|mv| mv.to_packed() == packed))
+    {
+        moves[..=pos].rotate_right(1);
+    }
+
+    let mut best_move = None;
+
+    for mv in moves {
+        let undo = board.make_move(mv.clone());
+        let score = -negamax(board, depth - 1, -beta, -alpha, tt.as_deref_mut());
+        board.unmake_move(mv.clone(), undo);
+
+        if score >= beta {
+            alpha = beta;
+            best_move = Some(mv);
+            break;
+        }
+
+        if score > alpha {
+            alpha = score;
+            best_move = Some(mv);
+        }
+    }
+
+    if let Some(tt) = tt {
+        tt.store(TtEntry {
+            best_move: best_move.map(|mv| mv.to_packed()),
+            bound: Bound::of(alpha, alpha_orig, beta),
+            depth,
+            key: board.hash,
+            score: alpha,
+        });
+    }
+
+    alpha
+}
+
+fn negamax_unpruned(board: &mut Board, depth: usize) -> i32 {
+    let moves = move_generator::legal_moves(board);
+
+    if let Some(score) = terminal_score(board, &moves, depth) {
+        return score;
+    }
+
+    if depth == 0 {
+        return quiescence(board, -INFINITY, INFINITY);
+    }
+
+    let mut best_score = -INFINITY;
+
+    for mv in moves {
+        let undo = board.make_move(mv.clone());
+        let score = -negamax_unpruned(board, depth - 1);
+        board.unmake_move(mv, undo);
+
+        best_score = best_score.max(score);
+    }
+
+    best_score
+}
+
+fn search(board: &Board, depth: usize) -> Option<(i32, Move)> {
+    let mut board = board.clone();
+    let mut best: Option<(i32, Move)> = None;
+
+    for mv in move_generator::legal_moves(&board) {
+        let undo = board.make_move(mv.clone());
+        let score = -negamax_unpruned(&mut board, depth.saturating_sub(1));
+        board.unmake_move(mv.clone(), undo);
+
+        if best
+            .as_ref()
+            .is_none_or(|(best_score, _)| score > *best_score)
+        {
+            best = Some((score, mv));
+        }
+    }
+
+    best
+}
+
+fn search_ab(board: &Board, depth: usize) -> Option<(i32, Move)> {
+    let mut moves = move_generator::legal_moves(board);
+    order_moves(board, &mut moves);
+
+    search_ab_until(board, depth, &moves, None, None)
+}
+
+fn search_tt(board: &Board, depth: usize, tt: &mut TranspositionTable) -> Option<(i32, Move)> {
+    let mut moves = move_generator::legal_moves(board);
+    order_moves(board, &mut moves);
+
+    search_ab_until(board, depth, &moves, None, Some(tt))
+}
+
+/// Searches the given root moves in order.
+///
+/// Returns `None` if there are no moves, or the search has been aborted because
+/// the deadline passed.
+fn search_ab_until(
+    board: &Board,
+    depth: usize,
+    moves: &[Move],
+    deadline: Option<Instant>,
+    mut tt: Option<&mut TranspositionTable>,
+) -> Option<(i32, Move)> {
+    let mut board = board.clone();
+    let mut best: Option<(i32, Move)> = None;
+    let mut alpha = -INFINITY;
+
+    for mv in moves.iter().cloned() {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return None;
+        }
+
+        let undo = board.make_move(mv.clone());
+        let score = -negamax(
+            &mut board,
+            depth.saturating_sub(1),
+            -INFINITY,
+            -alpha,
+            tt.as_deref_mut(),
+        );
+        board.unmake_move(mv.clone(), undo);
+
+        if best.is_none() || score > alpha {
+            alpha = score;
+            best = Some((score, mv));
+        }
+    }
+
+    best
+}
+
+/// How the score of a [`TtEntry`] relates to the actual score of the position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is exact.
+    Exact,
+    /// The actual score is at least the stored one (the search failed high).
+    Lower,
+    /// The actual score is at most the stored one (the search failed low).
+    Upper,
+}
+
+impl Bound {
+    /// The bound of a score, returned by a search with the given window.
+    fn of(score: i32, alpha: i32, beta: i32) -> Self {
+        if score <= alpha {
+            Bound::Upper
+        } else if score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        }
+    }
+}
+
+/// The result of searching a position, see [`TranspositionTable`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TtEntry {
+    /// The best move found, encoded with [`Move::to_packed`]. Is `None` if no move
+    /// raised alpha.
+    pub best_move: Option<u16>,
+    pub bound: Bound,
+    /// The remaining depth the position was searched with.
+    pub depth: usize,
+    /// The zobrist key ([`Board::zobrist`]) of the position. The search uses the
+    /// incrementally updated hash of the board, which always equals it.
+    pub key: u64,
+    pub score: i32,
+}
+
+/// Caches the results of searched positions, so that transpositions (the same
+/// position reached by different move orders) don't have to be searched again.
+///
+/// The table has a fixed size, and the entries are indexed by their key modulo the
+/// size. A new entry replaces the previous one in its slot.
+pub struct TranspositionTable {
+    entries: Vec<Option<TtEntry>>,
+}
+
+impl TranspositionTable {
+    /// Creates a table with `size` entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is `0`.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "transposition table needs at least one entry");
+
+        Self {
+            entries: vec![None; size],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+
+    /// Get the entry of the position with the given key, searched to exactly the
+    /// given depth.
+    ///
+    /// Results of deeper searches are not returned, so that the search results are
+    /// the same as without the table. They would also score mates differently,
+    /// since mate scores depend on the remaining depth.
+    pub fn probe(&self, key: u64, depth: usize) -> Option<&TtEntry> {
+        self.entries[self.index(key)]
+            .as_ref()
+            .filter(|entry| entry.key == key && entry.depth == depth)
+    }
+
+    pub fn store(&mut self, entry: TtEntry) {
+        let i = self.index(entry.key);
+
+        self.entries[i] = Some(entry);
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (key % self.entries.len() as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::Fen, Color::*, Piece::*, Square::*};
+
+    use super::*;
+
+    #[test]
+    fn mate_in_one() {
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+
+        assert_eq!(best_move(&board, 2), Some(Move::new(White, Rook, A1, A8)));
+    }
+
+    #[test]
+    fn captures_hanging_queen() {
+        let board = Board::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(best_move(&board, 1), Some(Move::new(White, Rook, D2, D5)));
+    }
+
+    #[test]
+    fn black_captures_hanging_queen() {
+        let board = Board::from_fen("4k3/3r4/8/8/3Q4/8/8/4K3 b - - 0 1").unwrap();
+
+        assert_eq!(best_move(&board, 2), Some(Move::new(Black, Rook, D7, D4)));
+    }
+
+    #[test]
+    fn mate_in_one_pruned() {
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+
+        assert_eq!(
+            best_move_ab(&board, 3),
+            Some(Move::new(White, Rook, A1, A8))
+        );
+    }
+
+    #[test]
+    fn search_timed_mate_in_one() {
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let start = Instant::now();
+
+        let (mv, score) = search_timed(&board, 10_000).unwrap();
+
+        assert_eq!(mv, Move::new(White, Rook, A1, A8));
+        assert!(score >= MATE_SCORE);
+        // The search stops as soon as the mate is found.
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn search_timed_respects_budget() {
+        let board = Board::new_with_standard_formation();
+        let start = Instant::now();
+
+        assert!(search_timed(&board, 200).is_some());
+        assert!(start.elapsed() < Duration::from_millis(2_000));
+
+        let board = Board::from_fen("7k/5KQ1/8/8/8/8/8/8 b - - 0 1").unwrap();
+
+        assert_eq!(search_timed(&board, 200), None);
+    }
+
+    #[test]
+    fn pruned_matches_unpruned_starting_position() {
+        let board = Board::new_with_standard_formation();
+
+        assert_eq!(
+            search_ab(&board, 3).map(|(score, _)| score),
+            search(&board, 3).map(|(score, _)| score)
+        );
+    }
+
+    #[test]
+    fn transposition_table_matches_plain_alpha_beta() {
+        let mut tt = TranspositionTable::new(1 << 16);
+
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+            "4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+
+            for depth in 1..=3 {
+                // The table is reused on purpose, to include entries of previous
+                // searches.
+                assert_eq!(
+                    search_tt(&board, depth, &mut tt).map(|(score, _)| score),
+                    search_ab(&board, depth).map(|(score, _)| score),
+                    "{} at depth {}",
+                    fen,
+                    depth
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn transposition_table_probe() {
+        let mut tt = TranspositionTable::new(8);
+        let entry = TtEntry {
+            best_move: None,
+            bound: Bound::Exact,
+            depth: 2,
+            key: 42,
+            score: 7,
+        };
+
+        tt.store(entry.clone());
+
+        assert_eq!(tt.probe(42, 2), Some(&entry));
+        assert_eq!(tt.probe(42, 3), None);
+        // Same slot, but a different key.
+        assert_eq!(tt.probe(42 + 8, 2), None);
+
+        tt.clear();
+
+        assert_eq!(tt.probe(42, 2), None);
+    }
+
+    #[test]
+    fn transposition_table_keyed_on_zobrist() {
+        let mut tt = TranspositionTable::new(1 << 16);
+
+        search_tt(&Board::default(), 2, &mut tt);
+
+        let after_e4 =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+
+        assert!(tt.probe(after_e4.zobrist(), 1).is_some());
+    }
+
+    #[test]
+    fn pruned_matches_unpruned() {
+        // Kiwipete is only searched to depth 1, since the unpruned quiescence search
+        // of its many capture sequences takes minutes at depth 2.
+        for (fen, max_depth) in [
+            ("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 2),
+            ("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 2),
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                1,
+            ),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 2),
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+
+            for depth in 1..=max_depth {
+                assert_eq!(
+                    search_ab(&board, depth).map(|(score, _)| score),
+                    search(&board, depth).map(|(score, _)| score),
+                    "{} at depth {}",
+                    fen,
+                    depth
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn quiescence_resolves_capture_sequence() {
+        // White just captured a defended pawn with the queen.
+        let mut board = Board::from_fen("4k3/8/2p5/3Q4/8/8/8/4K3 b - - 0 1").unwrap();
+
+        // Statically, white is up a queen for a pawn, but the queen is lost.
+        assert!(evaluation::evaluate_relative(&board) < -500);
+        assert!(quiescence(&mut board, -INFINITY, INFINITY) > 0);
+    }
+
+    #[test]
+    fn avoids_defended_pawn() {
+        let board = Board::from_fen("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1").unwrap();
+
+        assert_ne!(
+            best_move_ab(&board, 1),
+            Some(Move::new(White, Queen, D1, D5))
+        );
+    }
+
+    #[test]
+    fn order_moves_most_valuable_victim_first() {
+        let board = Board::from_fen("4k3/8/8/3q4/8/8/p2R4/4K3 w - - 0 1").unwrap();
+
+        let mut moves = move_generator::legal_moves(&board);
+        order_moves(&board, &mut moves);
+
+        assert_eq!(moves[0], Move::new(White, Rook, D2, D5));
+        assert_eq!(moves[1], Move::new(White, Rook, D2, A2));
+    }
+
+    #[test]
+    fn order_moves_least_valuable_attacker_first() {
+        let board = Board::from_fen("4k3/8/8/3r4/2P5/8/8/3QK3 w - - 0 1").unwrap();
+
+        let mut moves = move_generator::legal_moves(&board);
+        order_moves(&board, &mut moves);
+
+        assert_eq!(moves[0], Move::new(White, Pawn, C4, D5));
+        assert_eq!(moves[1], Move::new(White, Queen, D1, D5));
+    }
+
+    #[test]
+    fn no_legal_moves() {
+        let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+
+        assert_eq!(best_move(&board, 2), None);
+        assert_eq!(best_move_ab(&board, 2), None);
+    }
+}
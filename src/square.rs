@@ -1,7 +1,13 @@
-use std::fmt::Debug;
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use crate::fen::FEN_SQUARE_SYMBOL_LOOKUP;
 
 #[rustfmt::skip]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Square {
     A8, B8, C8, D8, E8, F8, G8, H8,
     A7, B7, C7, D7, E7, F7, G7, H7,
@@ -13,6 +19,76 @@ pub enum Square {
     A1, B1, C1, D1, E1, F1, G1, H1,
 }
 
+impl Square {
+    /// All squares, in the same order as their indices.
+    #[rustfmt::skip]
+    pub const ALL: [Square; 64] = {
+        use Square::*;
+
+        [
+            A8, B8, C8, D8, E8, F8, G8, H8,
+            A7, B7, C7, D7, E7, F7, G7, H7,
+            A6, B6, C6, D6, E6, F6, G6, H6,
+            A5, B5, C5, D5, E5, F5, G5, H5,
+            A4, B4, C4, D4, E4, F4, G4, H4,
+            A3, B3, C3, D3, E3, F3, G3, H3,
+            A2, B2, C2, D2, E2, F2, G2, H2,
+            A1, B1, C1, D1, E1, F1, G1, H1,
+        ]
+    };
+
+    /// Creates the square from its file (`0` = a-file) and rank.
+    ///
+    /// The rank follows the index layout of the board, meaning `0` is the 8th rank and
+    /// `7` the 1st one.
+    pub fn from_file_rank(file: u8, rank: u8) -> Result<Square, String> {
+        if file >= 8 || rank >= 8 {
+            return Err(format!(
+                "file '{}' and rank '{}' are not valid, make sure they're in the range '0..8'",
+                file, rank
+            ));
+        }
+
+        Square::try_from(rank as usize * 8 + file as usize)
+    }
+
+    /// The file of the square, where `0` is the a-file and `7` the h-file.
+    pub fn file(self) -> u8 {
+        self as u8 % 8
+    }
+
+    /// Iterates over all squares, in the same order as their indices.
+    pub fn iter() -> impl Iterator<Item = Square> {
+        Square::ALL.into_iter()
+    }
+
+    /// The rank of the square, where `0` is the 8th rank and `7` the 1st one.
+    pub fn rank(self) -> u8 {
+        self as u8 / 8
+    }
+}
+
+impl Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", FEN_SQUARE_SYMBOL_LOOKUP[*self as usize])
+    }
+}
+
+impl FromStr for Square {
+    type Err = String;
+
+    /// Parses the square from its (case-insensitive) name, e.g. `"e4"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowercase = s.to_ascii_lowercase();
+
+        FEN_SQUARE_SYMBOL_LOOKUP
+            .iter()
+            .position(|sym| *sym == lowercase)
+            .ok_or_else(|| format!("could not identify square with name '{}'", s))
+            .and_then(Square::try_from)
+    }
+}
+
 impl From<Square> for i8 {
     fn from(square: Square) -> Self {
         square as i8
@@ -31,25 +107,26 @@ impl From<Square> for usize {
     }
 }
 
-impl TryFrom<usize> for Square {
+impl TryFrom<i8> for Square {
     type Error = String;
 
-    fn try_from(value: usize) -> Result<Self, Self::Error> {
-        use Square::*;
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        usize::try_from(value)
+            .map_err(|_| {
+                format!(
+                    "index '{}' is not valid, make sure it's in the range '0..64'",
+                    value
+                )
+            })
+            .and_then(Square::try_from)
+    }
+}
 
-        #[rustfmt::skip]
-        const LOOKUP: [Square; 64] = [
-            A8, B8, C8, D8, E8, F8, G8, H8,
-            A7, B7, C7, D7, E7, F7, G7, H7,
-            A6, B6, C6, D6, E6, F6, G6, H6,
-            A5, B5, C5, D5, E5, F5, G5, H5,
-            A4, B4, C4, D4, E4, F4, G4, H4,
-            A3, B3, C3, D3, E3, F3, G3, H3,
-            A2, B2, C2, D2, E2, F2, G2, H2,
-            A1, B1, C1, D1, E1, F1, G1, H1,
-        ];
+impl TryFrom<usize> for Square {
+    type Error = String;
 
-        LOOKUP
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Square::ALL
             .get(value)
             .ok_or_else(|| {
                 format!(
@@ -57,6 +134,86 @@ impl TryFrom<usize> for Square {
                     value
                 )
             })
-            .map(|s| *s)
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use Square::*;
+
+    #[test]
+    fn file_rank_corners() {
+        assert_eq!((A8.file(), A8.rank()), (0, 0));
+        assert_eq!((H8.file(), H8.rank()), (7, 0));
+        assert_eq!((A1.file(), A1.rank()), (0, 7));
+        assert_eq!((H1.file(), H1.rank()), (7, 7));
+    }
+
+    #[test]
+    fn from_file_rank() {
+        assert_eq!(Square::from_file_rank(0, 0), Ok(A8));
+        assert_eq!(Square::from_file_rank(7, 7), Ok(H1));
+        assert_eq!(Square::from_file_rank(4, 4), Ok(E4));
+    }
+
+    #[test]
+    fn from_file_rank_out_of_range() {
+        assert!(Square::from_file_rank(8, 0).is_err());
+        assert!(Square::from_file_rank(0, 8).is_err());
+        assert!(Square::from_file_rank(u8::MAX, u8::MAX).is_err());
+    }
+
+    #[test]
+    fn all() {
+        assert_eq!(Square::ALL.len(), 64);
+        assert_eq!(Square::ALL[0], A8);
+        assert_eq!(Square::ALL[63], H1);
+    }
+
+    #[test]
+    fn iter_in_index_order() {
+        for (idx, square) in Square::iter().enumerate() {
+            assert_eq!(usize::from(square), idx);
+        }
+
+        assert_eq!(Square::iter().count(), 64);
+    }
+
+    #[test]
+    fn try_from_usize() {
+        assert_eq!(Square::try_from(0usize), Ok(A8));
+        assert_eq!(Square::try_from(63usize), Ok(H1));
+        assert!(Square::try_from(64usize).is_err());
+    }
+
+    #[test]
+    fn try_from_i8() {
+        assert_eq!(Square::try_from(0i8), Ok(A8));
+        assert_eq!(Square::try_from(63i8), Ok(H1));
+        assert!(Square::try_from(64i8).is_err());
+        assert!(Square::try_from(-1i8).is_err());
+    }
+
+    #[test]
+    fn display_from_str_round_trip() {
+        for square in Square::iter() {
+            assert_eq!(square.to_string().parse(), Ok(square));
+        }
+    }
+
+    #[test]
+    fn from_str_case_insensitive() {
+        assert_eq!(E4.to_string(), "e4");
+        assert_eq!("E4".parse(), Ok(E4));
+    }
+
+    #[test]
+    fn from_str_invalid() {
+        for s in ["z9", "e", "", "e44", "4e"] {
+            assert!(s.parse::<Square>().is_err(), "{}", s);
+        }
     }
 }
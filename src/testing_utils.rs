@@ -1,4 +1,9 @@
-use crate::{bit_board, Piece};
+use crate::{
+    bit_board,
+    board::PieceInstance,
+    Board, Color,
+    Piece::{self, *},
+};
 
 pub fn assert_bit_boards_eq(left: u64, right: u64) {
     assert_eq!(
@@ -10,15 +15,104 @@ pub fn assert_bit_boards_eq(left: u64, right: u64) {
     );
 }
 
-impl Piece {
-    pub fn all_variants() -> [Piece; 6] {
-        [
-            Piece::Bishop,
-            Piece::King,
-            Piece::Knight,
-            Piece::Pawn,
-            Piece::Queen,
-            Piece::Rook,
-        ]
+/// Generates a random position, which is always the same for the same seed.
+///
+/// Besides the two kings, up to a few pieces of every other type are placed per
+/// color. Pawns are never placed on the first or last rank, and the side that is
+/// not to move is never in check. Castling rights and en passant targets are
+/// not set.
+pub fn random_legal_position(seed: u64) -> Board {
+    const MAX_PIECES_PER_TYPE: u64 = 3;
+
+    let mut rng = SplitMix64(seed);
+
+    loop {
+        let mut board = Board::new_empty();
+        board.set_side_to_move(match rng.next().is_multiple_of(2) {
+            true => Color::White,
+            false => Color::Black,
+        });
+
+        for color in [Color::Black, Color::White] {
+            place_randomly(&mut board, &mut rng, PieceInstance::new(color, King));
+
+            for piece in [Bishop, Knight, Pawn, Queen, Rook] {
+                for _ in 0..rng.next() % (MAX_PIECES_PER_TYPE + 1) {
+                    place_randomly(&mut board, &mut rng, PieceInstance::new(color, piece));
+                }
+            }
+        }
+
+        let stm = board.side_to_move();
+        let opp = stm.opposing();
+        // This also rules out kings standing next to each other.
+        let is_opp_in_check = board
+            .king_square(opp)
+            .is_some_and(|king_pos| board.is_pos_attacked_by(king_pos, &stm));
+
+        if !is_opp_in_check {
+            return board;
+        }
+    }
+
+    fn place_randomly(board: &mut Board, rng: &mut SplitMix64, ins: PieceInstance) {
+        loop {
+            let idx = (rng.next() % Board::SIZE as u64) as usize;
+            let is_back_rank = !(Board::WIDTH..Board::SIZE - Board::WIDTH).contains(&idx);
+
+            if board.is_square_empty(idx) && !(ins.piece == Piece::Pawn && is_back_rank) {
+                board.set_piece(idx, ins);
+                return;
+            }
+        }
+    }
+}
+
+/// A small pseudo random number generator, which is fine with any seed.
+///
+/// For more information, visit: https://prng.di.unimi.it/splitmix64.c
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_legal_position_sanity() {
+        const BACK_RANKS: u64 = 0xff | (0xff << 56);
+
+        for seed in 0..1000 {
+            let board = random_legal_position(seed);
+            let opp = board.side_to_move().opposing();
+
+            assert_eq!(board.material_count()[Color::Black][King as usize], 1);
+            assert_eq!(board.material_count()[Color::White][King as usize], 1);
+            assert_eq!(
+                (board.pawns[Color::Black] | board.pawns[Color::White]) & BACK_RANKS,
+                0,
+                "{}",
+                seed
+            );
+            assert!(
+                !board.is_pos_attacked_by(board.king_square(opp).unwrap(), &opp.opposing()),
+                "{}",
+                seed
+            );
+            assert_eq!(board.hash, board.zobrist());
+        }
+
+        assert_eq!(random_legal_position(42), random_legal_position(42));
     }
 }
@@ -0,0 +1,74 @@
+use once_cell::sync::Lazy;
+
+use crate::{
+    castling_rights::{CastlingRights, CastlingSide},
+    Board, Color, Piece,
+};
+
+static KEYS: Lazy<Keys> = Lazy::new(Keys::generate);
+
+/// Random numbers used to calculate [`Board::zobrist`].
+struct Keys {
+    castling_rights: [u64; 4],
+    en_passant_files: [u64; Board::WIDTH],
+    /// Indexed by piece, color, and square.
+    pieces: [[[u64; Board::SIZE]; 2]; 6],
+    white_to_move: u64,
+}
+
+impl Keys {
+    fn generate() -> Self {
+        // Xorshift with a fixed seed, so the keys are the same on every run.
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut random_u64 = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let mut keys = Self {
+            castling_rights: [0; 4],
+            en_passant_files: [0; Board::WIDTH],
+            pieces: [[[0; Board::SIZE]; 2]; 6],
+            white_to_move: random_u64(),
+        };
+
+        keys.castling_rights.fill_with(&mut random_u64);
+        keys.en_passant_files.fill_with(&mut random_u64);
+        for per_color in keys.pieces.iter_mut() {
+            for per_square in per_color.iter_mut() {
+                per_square.fill_with(&mut random_u64);
+            }
+        }
+
+        keys
+    }
+}
+
+/// The key of the given castling rights combined.
+pub fn castling_rights(castling_rights: CastlingRights) -> u64 {
+    [Color::Black, Color::White]
+        .into_iter()
+        .flat_map(|color| CastlingSide::all().map(|side| (color, side)))
+        .zip(KEYS.castling_rights)
+        .filter(|((color, side), _)| castling_rights.has(*color, *side))
+        .fold(0, |key, (_, right_key)| key ^ right_key)
+}
+
+/// The key of the file of the en passant target, or `0` if there is none.
+pub fn en_passant(en_passant_target_idx: Option<usize>) -> u64 {
+    en_passant_target_idx
+        .map(|idx| KEYS.en_passant_files[idx % Board::WIDTH])
+        .unwrap_or(0)
+}
+
+/// The key of a piece of the given color on the given square.
+pub fn piece(color: Color, piece: Piece, pos: usize) -> u64 {
+    KEYS.pieces[piece as usize][color as usize][pos]
+}
+
+/// The key that is added, when it's white's turn.
+pub fn white_to_move() -> u64 {
+    KEYS.white_to_move
+}
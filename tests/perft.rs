@@ -4,68 +4,16 @@
 //! Read more about perft: https://www.chessprogramming.org/Perft
 //! Validated perft results: https://www.chessprogramming.org/Perft_Results
 
-use chess_logic::{
-    board::PieceInstance,
-    fen::Fen,
-    move_generator::{self},
-    Board, Square,
-};
-
-fn perft(board: &Board, depth: usize, root: bool) -> usize {
-    let mut nodes = 0;
-
-    if depth == 0 {
-        return 1;
-    }
-
-    let moves = move_generator::all_moves(board);
-
-    for mv in moves {
-        let mv_src = mv.src();
-        let mv_dst = mv.dst();
-        let mv_prom = mv.prom_to();
-        let mv_color = mv.piece_color();
-
-        let mut board = board.clone();
-
-        if !board.do_move(mv) {
-            continue;
-        }
-
-        let cnt = perft(&board, depth - 1, false);
-        nodes += cnt;
-
-        if root {
-            print!(
-                "{}{}",
-                format!("{:?}", Square::try_from(mv_src).unwrap()).to_lowercase(),
-                format!("{:?}", Square::try_from(mv_dst).unwrap()).to_lowercase(),
-            );
-
-            if let Some(prom_to) = mv_prom {
-                let ins = PieceInstance::new(mv_color, prom_to);
-                print!("{}", ins.get_fen());
-            }
-
-            println!(": {}", cnt);
-        }
-    }
-
-    if root {
-        println!("\nNodes searched: {}", nodes);
-    }
-
-    return nodes;
-}
+use chess_logic::{fen::Fen, move_generator::perft, Board};
 
 #[test]
 fn initial_position() {
     let board =
         Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 ").unwrap();
 
-    assert_eq!(perft(&board, 1, true), 20);
-    assert_eq!(perft(&board, 2, true), 400);
-    assert_eq!(perft(&board, 3, true), 8_902);
+    assert_eq!(perft(&board, 1), 20);
+    assert_eq!(perft(&board, 2), 400);
+    assert_eq!(perft(&board, 3), 8_902);
 }
 
 #[test]
@@ -74,18 +22,18 @@ fn position_2() {
         Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - ")
             .unwrap();
 
-    assert_eq!(perft(&board, 1, true), 48);
-    assert_eq!(perft(&board, 2, true), 2_039);
-    assert_eq!(perft(&board, 3, true), 97_862);
+    assert_eq!(perft(&board, 1), 48);
+    assert_eq!(perft(&board, 2), 2_039);
+    assert_eq!(perft(&board, 3), 97_862);
 }
 
 #[test]
 fn position_3() {
     let board = Board::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - ").unwrap();
 
-    assert_eq!(perft(&board, 1, true), 14);
-    assert_eq!(perft(&board, 2, true), 191);
-    assert_eq!(perft(&board, 3, true), 2812);
+    assert_eq!(perft(&board, 1), 14);
+    assert_eq!(perft(&board, 2), 191);
+    assert_eq!(perft(&board, 3), 2812);
 }
 
 #[test]
@@ -93,9 +41,9 @@ fn position_4() {
     let board = Board::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1")
         .unwrap();
 
-    assert_eq!(perft(&board, 1, true), 6);
-    assert_eq!(perft(&board, 2, true), 264);
-    assert_eq!(perft(&board, 3, true), 9_467);
+    assert_eq!(perft(&board, 1), 6);
+    assert_eq!(perft(&board, 2), 264);
+    assert_eq!(perft(&board, 3), 9_467);
 }
 
 #[test]
@@ -103,9 +51,9 @@ fn position_5() {
     let board =
         Board::from_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8").unwrap();
 
-    assert_eq!(perft(&board, 1, true), 44);
-    assert_eq!(perft(&board, 2, true), 1_486);
-    assert_eq!(perft(&board, 3, true), 62_379);
+    assert_eq!(perft(&board, 1), 44);
+    assert_eq!(perft(&board, 2), 1_486);
+    assert_eq!(perft(&board, 3), 62_379);
 }
 
 #[test]
@@ -114,7 +62,72 @@ fn position_6() {
         Board::from_fen("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10")
             .unwrap();
 
-    assert_eq!(perft(&board, 1, true), 46);
-    assert_eq!(perft(&board, 2, true), 2_079);
-    assert_eq!(perft(&board, 3, true), 89_890);
+    assert_eq!(perft(&board, 1), 46);
+    assert_eq!(perft(&board, 2), 2_079);
+    assert_eq!(perft(&board, 3), 89_890);
+}
+
+// Position 3 stays small enough to always be searched to depth 5, which reaches
+// the en passant captures that would expose the king along the rank.
+#[test]
+fn position_3_deep() {
+    let board = Board::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - ").unwrap();
+
+    assert_eq!(perft(&board, 4), 43_238);
+    assert_eq!(perft(&board, 5), 674_624);
+}
+
+// The deeper searches take a long time, and are thus only run on demand:
+// `cargo test --release -- --ignored`
+
+#[test]
+#[ignore]
+fn initial_position_deep() {
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 ").unwrap();
+
+    assert_eq!(perft(&board, 4), 197_281);
+    assert_eq!(perft(&board, 5), 4_865_609);
+}
+
+#[test]
+#[ignore]
+fn position_2_deep() {
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - ")
+            .unwrap();
+
+    assert_eq!(perft(&board, 4), 4_085_603);
+    assert_eq!(perft(&board, 5), 193_690_690);
+}
+
+#[test]
+#[ignore]
+fn position_4_deep() {
+    let board = Board::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1")
+        .unwrap();
+
+    assert_eq!(perft(&board, 4), 422_333);
+    assert_eq!(perft(&board, 5), 15_833_292);
+}
+
+#[test]
+#[ignore]
+fn position_5_deep() {
+    let board =
+        Board::from_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8").unwrap();
+
+    assert_eq!(perft(&board, 4), 2_103_487);
+    assert_eq!(perft(&board, 5), 89_941_194);
+}
+
+#[test]
+#[ignore]
+fn position_6_deep() {
+    let board =
+        Board::from_fen("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10")
+            .unwrap();
+
+    assert_eq!(perft(&board, 4), 3_894_594);
+    assert_eq!(perft(&board, 5), 164_075_551);
 }